@@ -0,0 +1,69 @@
+//! Counts the allocations made by a single run of each part of every day against each input
+//! profile, alongside how long the run took:
+//!
+//! ```sh
+//! cargo bench --bench allocations [-- <day name filter>]
+//! ```
+
+use aoc_2021::{
+    allocations::{self, Counting},
+    input::{self, Source},
+    Part, DAYS,
+};
+use criterion::black_box;
+use std::{env, path::Path, time::Instant};
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn main() {
+    // Cargo passes `--bench` to every bench target, so only the other arguments are filters.
+    let filter = env::args().skip(1).find(|arg| !arg.starts_with("--"));
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join(input::DEFAULT_DIRECTORY);
+    let profiles = input::profiles(&directory).expect("Could not read input profiles");
+
+    println!(
+        "{:<14}  {:<10}  {:<8}  {:>12}  {:>14}  {:>10}",
+        "Day", "Profile", "Part", "Allocations", "Bytes", "Time"
+    );
+
+    for day in DAYS {
+        if filter
+            .as_ref()
+            .is_some_and(|filter| !day.name.contains(filter.as_str()))
+        {
+            continue;
+        }
+
+        for profile in &profiles {
+            let input = match Source::Directory(directory.join(profile)).load(day) {
+                Ok(input) => input,
+                Err(error) => {
+                    println!(
+                        "{:<14}  {:<10}  {:<8}  error: {}",
+                        day.name, profile, "-", error
+                    );
+                    continue;
+                }
+            };
+
+            for (name, part) in [("part_one", Part::One), ("part_two", Part::Two)] {
+                let start = Instant::now();
+                let (answer, counts) =
+                    allocations::measure(|| day.solve(black_box(&input), black_box(part)));
+                let elapsed = start.elapsed();
+
+                match answer {
+                    Ok(_) => println!(
+                        "{:<14}  {:<10}  {:<8}  {:>12}  {:>14}  {:>10.2?}",
+                        day.name, profile, name, counts.allocations, counts.bytes, elapsed
+                    ),
+                    Err(error) => println!(
+                        "{:<14}  {:<10}  {:<8}  error: {}",
+                        day.name, profile, name, error
+                    ),
+                }
+            }
+        }
+    }
+}
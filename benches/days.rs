@@ -0,0 +1,40 @@
+use aoc_2021::{
+    input::{self, Source},
+    Part, DAYS,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::path::Path;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join(input::DEFAULT_DIRECTORY);
+    let profiles = input::profiles(&directory).expect("Could not read input profiles");
+
+    for day in DAYS {
+        let mut group = c.benchmark_group(day.name);
+        group.sample_size(10);
+
+        for profile in &profiles {
+            let source = Source::Directory(directory.join(profile));
+
+            // Not every profile has an input for every day.
+            if source.path(day).is_some_and(|path| !path.exists()) {
+                continue;
+            }
+
+            let input = source
+                .load(day)
+                .unwrap_or_else(|error| panic!("Could not load {} input: {}", profile, error));
+
+            for (name, part) in [("part_one", Part::One), ("part_two", Part::Two)] {
+                group.bench_function(format!("{}/{}", name, profile), |b| {
+                    b.iter(|| day.solve(black_box(&input), black_box(part)))
+                });
+            }
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|matrix: Vec<Vec<usize>>| {
+    let _ = aoc_2021::fuzzing::bingo_card(matrix);
+});
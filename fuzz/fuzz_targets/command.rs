@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc_2021::day_two::Command;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = input.parse::<Command>();
+});
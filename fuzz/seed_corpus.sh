@@ -0,0 +1,14 @@
+#!/bin/sh
+# Seeds the corpus of each day's solving and parsing fuzz targets with its input from every profile.
+set -e
+cd "$(dirname "$0")"
+
+for input in ../input/*/day_*.txt; do
+    target=$(basename "$input" .txt)
+    profile=$(basename "$(dirname "$input")")
+
+    for corpus in "corpus/$target" "corpus/parse_$target"; do
+        mkdir -p "$corpus"
+        cp "$input" "$corpus/$profile.txt"
+    done
+done
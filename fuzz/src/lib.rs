@@ -0,0 +1,34 @@
+//! Fuzz targets for the puzzle input parsers, run with
+//! [cargo-fuzz](https://github.com/rust-fuzz/cargo-fuzz) on a nightly toolchain:
+//!
+//! ```sh
+//! cd fuzz
+//! ./seed_corpus.sh
+//! cargo +nightly fuzz run day_four
+//! ```
+//!
+//! Each `day_*` target parses and solves a day, while each `parse_day_*` target only parses it, so
+//! that libFuzzer spends its time on the parser. The `command`, `fold`, `bingo_card` and
+//! `segments_to_sets` targets drive the parsers for single items of input directly.
+//!
+//! Any input may be rejected with an error, but none may cause a panic. Full-sized inputs to the
+//! path finding days, twelve and fifteen, are slow to solve in an instrumented build, so libFuzzer
+//! may report them as slow units rather than crashes.
+
+use aoc_2021::Part;
+
+/// Parses arbitrary `input` for the given `day`, discarding the model or error.
+pub fn parse(day: usize, input: &str) {
+    let day = aoc_2021::day(day).expect("Fuzzed day is registered");
+
+    let _ = day.parse(input);
+}
+
+/// Solves both parts of the given `day` from arbitrary `input`, discarding the answers or errors.
+pub fn solve(day: usize, input: &str) {
+    let day = aoc_2021::day(day).expect("Fuzzed day is registered");
+
+    for part in [Part::One, Part::Two] {
+        let _ = day.solve(input, part);
+    }
+}
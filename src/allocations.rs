@@ -0,0 +1,117 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    ops::Sub,
+};
+
+/// How many allocations were made, and how many bytes they asked for, on a single thread.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Counts {
+    pub allocations: usize,
+    pub bytes: usize,
+}
+
+impl Sub for Counts {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self {
+            allocations: self.allocations - other.allocations,
+            bytes: self.bytes - other.bytes,
+        }
+    }
+}
+
+thread_local! {
+    static COUNTS: Cell<Counts> = const {
+        Cell::new(Counts {
+            allocations: 0,
+            bytes: 0,
+        })
+    };
+}
+
+/// Counts every allocation made on each thread before handing it to the system allocator. Counts
+/// are only gathered once this is installed with `#[global_allocator]`, as the allocations bench
+/// does.
+pub struct Counting;
+
+impl Counting {
+    fn record(bytes: usize) {
+        // A thread's counts are gone while it is torn down, so its last allocations go uncounted.
+        let _ = COUNTS.try_with(|counts| {
+            let Counts {
+                allocations,
+                bytes: total,
+            } = counts.get();
+
+            counts.set(Counts {
+                allocations: allocations + 1,
+                bytes: total + bytes,
+            });
+        });
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    /// Growing or shrinking counts as a new allocation of `new_size` bytes, so that a `Vec` pushed
+    /// past its capacity shows up.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Returns the allocations made on the current thread so far.
+pub fn counts() -> Counts {
+    COUNTS.with(Cell::get)
+}
+
+/// Calls `f`, returning its result along with the allocations it made on the current thread.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Counts) {
+    let before = counts();
+    let result = f();
+
+    (result, counts() - before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counting, Counts};
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    fn test_measure_counts_allocations() {
+        let expected = Counts {
+            allocations: 2,
+            bytes: 16,
+        };
+        let (_, actual) = super::measure(|| (vec![0u8; 10], String::with_capacity(6)));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_measure_without_allocations() {
+        let expected = Counts::default();
+        let (_, actual) = super::measure(|| [1, 2, 3].iter().sum::<u32>());
+
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{input::Source, scaffold, Day, Error, Part, Result, DAYS};
+use std::{collections::BTreeMap, fmt};
+
+/// The manifest expected answers are read from unless another is given.
+pub const DEFAULT_MANIFEST: &str = "answers.toml";
+
+/// The expected answer to each day and part, for every input profile.
+///
+/// Answers are read from a small subset of TOML, in which each profile is a table and each
+/// answer is keyed by the registered day's name and the part, for example:
+///
+/// ```toml
+/// [default]
+/// day_one.part_one = 1266
+/// day_thirteen.part_two = "CJHAZHKU"
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Answers {
+    expected: BTreeMap<(String, usize, Part), String>,
+}
+
+impl Answers {
+    /// Parses the text of an answers manifest.
+    pub fn parse(manifest: &str) -> Result<Self> {
+        let mut expected = BTreeMap::new();
+        let mut profile = None;
+
+        for (ix, line) in manifest.lines().enumerate() {
+            let line_number = ix + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name
+                    .strip_suffix(']')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| {
+                        Error::manifest(line_number, line, "expected a table `[name]`")
+                    })?;
+                profile = Some(name.to_string());
+                continue;
+            }
+
+            let profile = profile
+                .clone()
+                .ok_or_else(|| Error::manifest(line_number, line, "answer is not in a table"))?;
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                Error::manifest(line_number, line, "expected an answer `day.part = value`")
+            })?;
+            let (day, part) = parse_key(key.trim()).map_err(|e| at_line(e, line_number))?;
+            let value = parse_value(value.trim()).map_err(|e| at_line(e, line_number))?;
+
+            if expected.insert((profile, day, part), value).is_some() {
+                return Err(Error::manifest(line_number, key.trim(), "duplicate answer"));
+            }
+        }
+
+        Ok(Self { expected })
+    }
+
+    /// Returns the expected answer to `part` of day `day` for the named input `profile`.
+    pub fn get(&self, profile: &str, day: usize, part: Part) -> Option<&str> {
+        self.expected
+            .get(&(profile.to_string(), day, part))
+            .map(String::as_str)
+    }
+}
+
+fn at_line(error: Error, line: usize) -> Error {
+    match error {
+        Error::Manifest { text, reason, .. } => Error::manifest(line, text, reason),
+        error => error,
+    }
+}
+
+fn parse_key(key: &str) -> Result<(usize, Part)> {
+    let (name, part) = key
+        .split_once('.')
+        .ok_or_else(|| Error::manifest(1, key, "expected a key `day.part`"))?;
+    // Answers may be kept for days whose features are disabled, so any day of the calendar will do.
+    let day = scaffold::day_of(name.trim())
+        .ok_or_else(|| Error::manifest(1, name.trim(), "unknown day"))?;
+    let part = match part.trim() {
+        "part_one" => Part::One,
+        "part_two" => Part::Two,
+        part => return Err(Error::manifest(1, part, "unknown part")),
+    };
+
+    Ok((day, part))
+}
+
+fn parse_value(value: &str) -> Result<String> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        quoted
+            .strip_suffix('"')
+            .filter(|s| !s.contains('"'))
+            .map(String::from)
+            .ok_or_else(|| Error::manifest(1, value, "invalid string"))
+    } else if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+        Ok(value.to_string())
+    } else {
+        Err(Error::manifest(1, value, "expected an integer or a string"))
+    }
+}
+
+/// How a solver's answer compared to the answer expected of it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// There is no expected answer to compare `actual` to.
+    Missing {
+        actual: String,
+    },
+    Error(Error),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail { expected, actual } => write!(
+                f,
+                "fail: expected {}, found {}",
+                expected,
+                single_line(actual)
+            ),
+            Self::Missing { actual } => write!(f, "missing: found {}", single_line(actual)),
+            Self::Error(error) => write!(f, "error: {}", error),
+        }
+    }
+}
+
+/// Joins the lines of a multi-line answer, such as a drawing, so it fits on one line.
+fn single_line(answer: &str) -> String {
+    answer.lines().collect::<Vec<_>>().join(" / ")
+}
+
+/// The outcome of verifying a single part of a single day.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Check {
+    pub day: usize,
+    pub name: &'static str,
+    pub part: Part,
+    pub outcome: Outcome,
+}
+
+/// Runs both parts of every registered day against its input from `source`, comparing each answer
+/// with the one expected for the named input `profile`.
+pub fn verify(answers: &Answers, profile: &str, source: &Source) -> Vec<Check> {
+    DAYS.iter()
+        .flat_map(|day| {
+            let input = source.load(day);
+
+            [Part::One, Part::Two].map(|part| Check {
+                day: day.number,
+                name: day.name,
+                part,
+                outcome: check(answers, profile, day, part, &input),
+            })
+        })
+        .collect()
+}
+
+fn check(
+    answers: &Answers,
+    profile: &str,
+    day: &Day,
+    part: Part,
+    input: &Result<String>,
+) -> Outcome {
+    let actual = match input.clone().and_then(|input| day.solve(&input, part)) {
+        Ok(actual) => actual,
+        Err(error) => return Outcome::Error(error),
+    };
+
+    match answers.get(profile, day.number, part) {
+        Some(expected) if expected == actual => Outcome::Pass,
+        Some(expected) => Outcome::Fail {
+            expected: expected.to_string(),
+            actual,
+        },
+        None => Outcome::Missing { actual },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answers;
+    use crate::{Error, Part};
+
+    fn use_example_manifest() -> &'static str {
+        r#"# Answers to the worked examples.
+[example]
+day_one.part_one = 7
+day_one.part_two = 5
+
+[default]
+day_thirteen.part_two = "CJHAZHKU"
+"#
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let answers = Answers::parse(use_example_manifest()).unwrap();
+        let expected = vec![Some("7"), Some("5"), None, Some("CJHAZHKU")];
+        let actual = vec![
+            answers.get("example", 1, Part::One),
+            answers.get("example", 1, Part::Two),
+            answers.get("default", 1, Part::One),
+            answers.get("default", 13, Part::Two),
+        ];
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_manifest_with_unknown_day() {
+        let manifest = "[default]\nday_one.part_one = 1266\nday_zero.part_one = 1\n";
+        let expected = Err(Error::manifest(3, "day_zero", "unknown day"));
+        let actual = Answers::parse(manifest);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_manifest_with_answer_outside_table() {
+        let manifest = "day_one.part_one = 1266\n";
+        let expected = Err(Error::manifest(
+            1,
+            "day_one.part_one = 1266",
+            "answer is not in a table",
+        ));
+        let actual = Answers::parse(manifest);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_one")]
+    fn test_verify_with_example_input() {
+        let directory = std::env::temp_dir().join("aoc_2021_answers_verify");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("day_one.txt"),
+            "199\n200\n208\n210\n200\n207\n240\n269\n260\n263",
+        )
+        .unwrap();
+
+        let answers = Answers::parse("[example]\nday_one.part_one = 7\n").unwrap();
+        let checks = super::verify(
+            &answers,
+            "example",
+            &crate::input::Source::Directory(directory),
+        );
+        let expected = vec![
+            super::Outcome::Pass,
+            super::Outcome::Missing {
+                actual: String::from("5"),
+            },
+        ];
+        let actual: Vec<super::Outcome> = checks
+            .into_iter()
+            .filter(|check| check.day == 1)
+            .map(|check| check.outcome)
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+}
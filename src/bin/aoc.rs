@@ -0,0 +1,486 @@
+#[cfg(feature = "fetch")]
+use aoc_2021::fetch::{self, Fetcher};
+use aoc_2021::{
+    answers::{self, Answers, Outcome},
+    generators,
+    input::{self, Source},
+    repl::Session,
+    report::{self, Format},
+    scaffold, Part,
+};
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    process, thread,
+};
+
+const USAGE: &str = "usage: aoc --day <1-25> --part <1|2> [--profile <name> | --all-profiles | --input-dir <dir>] [<input> | -]
+       aoc verify [--answers <file>] [--profile <name>]
+       aoc report [--format <table|json|markdown>] [--threads <n>] [--profile <name> | --input-dir <dir>]
+       aoc new --day <1-25>
+       aoc generate --day <1-25> --size <n> [--seed <n>]
+       aoc fetch --day <1-25> [--profile <name>] [--base-url <url>]
+       aoc repl";
+
+enum Command {
+    Solve(Args),
+    Verify(VerifyArgs),
+    Report(ReportArgs),
+    New(usize),
+    Generate(GenerateArgs),
+    #[cfg(feature = "fetch")]
+    Fetch(FetchArgs),
+    Repl,
+}
+
+/// Where the solve command reads its input from.
+enum Inputs {
+    Source(Source),
+    /// The input for the day from every profile in turn.
+    AllProfiles,
+}
+
+struct Args {
+    day: usize,
+    part: Part,
+    inputs: Inputs,
+}
+
+struct VerifyArgs {
+    answers: PathBuf,
+    /// The only profile to verify, rather than every profile.
+    profile: Option<String>,
+}
+
+struct ReportArgs {
+    format: Format,
+    threads: usize,
+    source: Source,
+}
+
+struct GenerateArgs {
+    day: usize,
+    size: usize,
+    seed: u64,
+}
+
+#[cfg(feature = "fetch")]
+struct FetchArgs {
+    day: usize,
+    profile: String,
+    base_url: String,
+}
+
+fn parse_command(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    match args.next() {
+        Some(arg) if arg == "verify" => parse_verify_args(args).map(Command::Verify),
+        Some(arg) if arg == "report" => parse_report_args(args).map(Command::Report),
+        Some(arg) if arg == "new" => parse_new_args(args).map(Command::New),
+        Some(arg) if arg == "generate" => parse_generate_args(args).map(Command::Generate),
+        #[cfg(feature = "fetch")]
+        Some(arg) if arg == "fetch" => parse_fetch_args(args).map(Command::Fetch),
+        Some(arg) if arg == "repl" => match args.next() {
+            Some(arg) => Err(format!("unexpected argument: {}", arg)),
+            None => Ok(Command::Repl),
+        },
+        Some(arg) => parse_args(Some(arg).into_iter().chain(args)).map(Command::Solve),
+        None => Err(String::from("missing --day")),
+    }
+}
+
+fn parse_verify_args(args: impl Iterator<Item = String>) -> Result<VerifyArgs, String> {
+    let mut answers = None;
+    let mut profile = None;
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" | "-a" => {
+                answers = Some(PathBuf::from(
+                    args.next().ok_or("missing value for --answers")?,
+                ));
+            }
+            "--profile" | "-P" => profile = Some(args.next().ok_or("missing value for --profile")?),
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+
+    Ok(VerifyArgs {
+        answers: answers.unwrap_or_else(|| PathBuf::from(answers::DEFAULT_MANIFEST)),
+        profile,
+    })
+}
+
+fn parse_report_args(args: impl Iterator<Item = String>) -> Result<ReportArgs, String> {
+    let mut format = None;
+    let mut threads = None;
+    let mut source = None;
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" | "-f" => {
+                let value = args.next().ok_or("missing value for --format")?;
+                format = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid format: {}", value))?,
+                );
+            }
+            "--threads" | "-j" => {
+                let value = args.next().ok_or("missing value for --threads")?;
+                threads = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|threads| *threads > 0)
+                        .ok_or_else(|| format!("invalid thread count: {}", value))?,
+                );
+            }
+            "--profile" | "-P" if source.is_none() => {
+                let value = args.next().ok_or("missing value for --profile")?;
+                source = Some(Source::profile(&value));
+            }
+            "--input-dir" | "-i" if source.is_none() => {
+                let value = args.next().ok_or("missing value for --input-dir")?;
+                source = Some(Source::Directory(PathBuf::from(value)));
+            }
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+
+    Ok(ReportArgs {
+        format: format.unwrap_or(Format::Table),
+        threads: threads.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get())),
+        source: source.unwrap_or_default(),
+    })
+}
+
+fn parse_new_args(args: impl Iterator<Item = String>) -> Result<usize, String> {
+    let mut day = None;
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                let value = args.next().ok_or("missing value for --day")?;
+                day = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid day: {}", value))?,
+                );
+            }
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+
+    day.ok_or_else(|| String::from("missing --day"))
+}
+
+fn parse_generate_args(args: impl Iterator<Item = String>) -> Result<GenerateArgs, String> {
+    let mut day = None;
+    let mut size = None;
+    let mut seed = None;
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                let value = args.next().ok_or("missing value for --day")?;
+                day = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid day: {}", value))?,
+                );
+            }
+            "--size" | "-n" => {
+                let value = args.next().ok_or("missing value for --size")?;
+                size = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid size: {}", value))?,
+                );
+            }
+            "--seed" | "-s" => {
+                let value = args.next().ok_or("missing value for --seed")?;
+                seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid seed: {}", value))?,
+                );
+            }
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+
+    Ok(GenerateArgs {
+        day: day.ok_or("missing --day")?,
+        size: size.ok_or("missing --size")?,
+        seed: seed.unwrap_or_default(),
+    })
+}
+
+#[cfg(feature = "fetch")]
+fn parse_fetch_args(args: impl Iterator<Item = String>) -> Result<FetchArgs, String> {
+    let mut day = None;
+    let mut profile = None;
+    let mut base_url = None;
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                let value = args.next().ok_or("missing value for --day")?;
+                day = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid day: {}", value))?,
+                );
+            }
+            "--profile" | "-P" => profile = Some(args.next().ok_or("missing value for --profile")?),
+            "--base-url" => base_url = Some(args.next().ok_or("missing value for --base-url")?),
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+
+    Ok(FetchArgs {
+        day: day.ok_or("missing --day")?,
+        profile: profile.unwrap_or_else(|| String::from(input::DEFAULT_PROFILE)),
+        base_url: base_url.unwrap_or_else(|| String::from(fetch::DEFAULT_BASE_URL)),
+    })
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut day = None;
+    let mut part = None;
+    let mut inputs = None;
+    let mut args = args;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => {
+                let value = args.next().ok_or("missing value for --day")?;
+                day = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid day: {}", value))?,
+                );
+            }
+            "--part" | "-p" => {
+                let value = args.next().ok_or("missing value for --part")?;
+                part = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid part: {}", value))?,
+                );
+            }
+            "--profile" | "-P" if inputs.is_none() => {
+                let value = args.next().ok_or("missing value for --profile")?;
+                inputs = Some(Inputs::Source(Source::profile(&value)));
+            }
+            "--all-profiles" if inputs.is_none() => inputs = Some(Inputs::AllProfiles),
+            "--input-dir" | "-i" if inputs.is_none() => {
+                let value = args.next().ok_or("missing value for --input-dir")?;
+                inputs = Some(Inputs::Source(Source::Directory(PathBuf::from(value))));
+            }
+            _ if inputs.is_none() && (arg == "-" || !arg.starts_with('-')) => {
+                inputs = Some(Inputs::Source(Source::from(arg.as_str())))
+            }
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+
+    Ok(Args {
+        day: day.ok_or("missing --day")?,
+        part: part.ok_or("missing --part")?,
+        inputs: inputs.unwrap_or_else(|| Inputs::Source(Source::default())),
+    })
+}
+
+fn main() {
+    let command = parse_command(env::args().skip(1)).unwrap_or_else(|message| {
+        eprintln!("{}\n{}", message, USAGE);
+        process::exit(2);
+    });
+
+    match command {
+        Command::Solve(args) => solve(args),
+        Command::Verify(args) => verify(args),
+        Command::New(day) => match scaffold::generate(Path::new("."), day) {
+            Ok(paths) => paths
+                .iter()
+                .for_each(|path| println!("wrote {}", path.display())),
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        },
+        Command::Generate(args) => match generators::generate(args.day, args.size, args.seed) {
+            Some(input) => print!("{}", input),
+            None => {
+                eprintln!("no generator for day {}", args.day);
+                process::exit(1);
+            }
+        },
+        #[cfg(feature = "fetch")]
+        Command::Fetch(args) => fetch(args),
+        Command::Report(args) => print!(
+            "{}",
+            report::render(&report::run_all(&args.source, args.threads), args.format)
+        ),
+        Command::Repl => repl(),
+    }
+}
+
+fn repl() {
+    let mut session = Session::new();
+    let mut lines = io::stdin().lock().lines();
+    println!("type `help` for commands");
+
+    loop {
+        print!("aoc> ");
+        io::stdout()
+            .flush()
+            .expect("Could not write to standard output");
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(error)) => {
+                eprintln!("could not read standard input: {}", error);
+                process::exit(1);
+            }
+            None => break,
+        };
+
+        match session.execute(&line) {
+            Some(output) if output.is_empty() => {}
+            Some(output) => println!("{}", output),
+            None => break,
+        }
+    }
+}
+
+fn solve(args: Args) {
+    let day = aoc_2021::day(args.day).unwrap_or_else(|| {
+        eprintln!("no solution for day {}", args.day);
+        process::exit(1);
+    });
+
+    match args.inputs {
+        Inputs::Source(source) => match source.solve(day, args.part) {
+            Ok(answer) => println!("{}", answer),
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        },
+        Inputs::AllProfiles => {
+            let mut failed = false;
+
+            for profile in all_profiles() {
+                match Source::profile(&profile).solve(day, args.part) {
+                    Ok(answer) => println!("{}: {}", profile, answer),
+                    Err(error) => {
+                        eprintln!("{}: {}", profile, error);
+                        failed = true;
+                    }
+                }
+            }
+
+            if failed {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// Returns every profile in the input directory, or exits if it cannot be read.
+fn all_profiles() -> Vec<String> {
+    input::profiles(input::DEFAULT_DIRECTORY).unwrap_or_else(|error| {
+        eprintln!("could not read {}: {}", input::DEFAULT_DIRECTORY, error);
+        process::exit(1);
+    })
+}
+
+#[cfg(feature = "fetch")]
+fn fetch(args: FetchArgs) {
+    let day = aoc_2021::day(args.day).unwrap_or_else(|| {
+        eprintln!("no solution for day {}", args.day);
+        process::exit(1);
+    });
+    let session = fetch::session(fetch::SESSION_FILE).unwrap_or_else(|| {
+        eprintln!(
+            "no session token, set {} or write it to {}",
+            fetch::SESSION_VARIABLE,
+            fetch::SESSION_FILE
+        );
+        process::exit(1);
+    });
+    let directory = Path::new(input::DEFAULT_DIRECTORY).join(&args.profile);
+
+    match Fetcher::new(session)
+        .with_base_url(args.base_url)
+        .fetch(day, directory)
+    {
+        Ok(path) => println!("{}", path.display()),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn verify(args: VerifyArgs) {
+    let answers = fs::read_to_string(&args.answers)
+        .map_err(|e| format!("could not read {}: {}", args.answers.display(), e))
+        .and_then(|manifest| Answers::parse(&manifest).map_err(|e| e.to_string()))
+        .unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        });
+
+    let profiles = args
+        .profile
+        .map_or_else(all_profiles, |profile| vec![profile]);
+    let mut disagreeing = Vec::new();
+
+    for profile in &profiles {
+        let checks = answers::verify(&answers, profile, &Source::profile(profile));
+        let (mut passed, mut failed, mut missing) = (0, 0, 0);
+
+        for check in checks {
+            match check.outcome {
+                Outcome::Pass => passed += 1,
+                Outcome::Missing { .. } => missing += 1,
+                Outcome::Fail { .. } | Outcome::Error(_) => failed += 1,
+            }
+
+            let part = match check.part {
+                Part::One => "part_one",
+                Part::Two => "part_two",
+            };
+            println!(
+                "{:<10} {:<14} {:<9} {}",
+                profile, check.name, part, check.outcome
+            );
+        }
+
+        println!(
+            "{}: {} passed, {} failed, {} missing\n",
+            profile, passed, failed, missing
+        );
+
+        if failed > 0 {
+            disagreeing.push(profile.as_str());
+        }
+    }
+
+    if !disagreeing.is_empty() {
+        println!(
+            "profiles disagreeing with their answers: {}",
+            disagreeing.join(", ")
+        );
+        process::exit(1);
+    }
+}
@@ -1,4 +1,8 @@
-use std::collections::{HashMap, HashSet};
+use crate::{error::column_of, process_results, Error, Part, Result, Solution};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeSet, HashMap},
+};
 
 #[derive(Default)]
 struct PatternMap {
@@ -6,129 +10,269 @@ struct PatternMap {
 }
 
 impl PatternMap {
-    fn get(&self, key: &HashSet<char>) -> Option<char> {
+    fn get(&self, key: &BTreeSet<char>) -> Option<char> {
         let key = Self::normalise_key(key);
-        self.mappings.get(&key).map(|c| *c)
+        self.mappings.get(&key).copied()
     }
 
-    fn insert(&mut self, key: &HashSet<char>, value: char) {
+    fn insert(&mut self, key: &BTreeSet<char>, value: char) {
         let key = Self::normalise_key(key);
         self.mappings.insert(key, value);
     }
 
-    fn normalise_key(key: &HashSet<char>) -> String {
-        let mut chars: Vec<char> = key.iter().map(|c| *c).collect();
-        chars.sort();
-        chars.into_iter().collect()
+    fn normalise_key(key: &BTreeSet<char>) -> String {
+        key.iter().collect()
     }
 }
 
-fn find_and_remove_by_len(sets: &mut Vec<HashSet<char>>, target_len: usize) -> HashSet<char> {
-    let value_ix = sets
-        .iter()
-        .position(|set| set.len() == target_len)
-        .unwrap()
-        .clone();
+fn find_and_remove_by_len(
+    sets: &mut Vec<BTreeSet<char>>,
+    target_len: usize,
+) -> Option<BTreeSet<char>> {
+    let value_ix = sets.iter().position(|set| set.len() == target_len)?;
 
-    sets.remove(value_ix)
+    Some(sets.remove(value_ix))
 }
 
 fn find_and_remove_by_len_and_differences(
-    sets: &mut Vec<HashSet<char>>,
+    sets: &mut Vec<BTreeSet<char>>,
     len: usize,
-    other: &HashSet<char>,
+    other: &BTreeSet<char>,
     differences: usize,
-) -> HashSet<char> {
+) -> Option<BTreeSet<char>> {
     let value_ix = sets
         .iter()
-        .position(|set| set.len() == len && set.difference(other).count() == differences)
-        .unwrap();
+        .position(|set| set.len() == len && set.difference(other).count() == differences)?;
 
-    sets.remove(value_ix)
+    Some(sets.remove(value_ix))
 }
 
-fn find_and_remove_unique_patterns(sets: &mut Vec<HashSet<char>>) -> [HashSet<char>; 4] {
-    [2, 3, 4, 7].map(|len| find_and_remove_by_len(sets, len))
+fn find_and_remove_unique_patterns(sets: &mut Vec<BTreeSet<char>>) -> Option<[BTreeSet<char>; 4]> {
+    let [one, seven, four, eight] = [2, 3, 4, 7].map(|len| find_and_remove_by_len(sets, len));
+    Some([one?, seven?, four?, eight?])
 }
 
-fn segments_to_sets(segments: &str) -> Vec<HashSet<char>> {
+pub(crate) fn segments_to_sets(segments: &str) -> Vec<BTreeSet<char>> {
     segments
         .split_whitespace()
-        .map(|segment| HashSet::from_iter(segment.chars()))
+        .map(|segment| BTreeSet::from_iter(segment.chars()))
         .collect()
 }
 
-fn count_unique_digits(input: Vec<String>) -> usize {
-    input
-        .into_iter()
-        .filter_map(|line| {
-            let (_, displayed_values) = line.split_once(" | ")?;
-            displayed_values
-                .split_whitespace()
-                .filter(|f| f.len() == 2 || f.len() == 3 || f.len() == 4 || f.len() == 7)
-                .count()
-                .into()
+/// A display's signal patterns and output values, each as the set of segments it lights.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(try_from = "Segments")
+)]
+pub struct Entry {
+    signal_patterns: Vec<BTreeSet<char>>,
+    display_values: Vec<BTreeSet<char>>,
+}
+
+/// The fields of a deserialised [`Entry`], before their segments are checked.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Segments {
+    signal_patterns: Vec<BTreeSet<char>>,
+    display_values: Vec<BTreeSet<char>>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Segments> for Entry {
+    type Error = String;
+
+    /// Accepts the segments only if, as in [`parse_entry`], every pattern and value lights at
+    /// least one segment and each segment is one of `a` to `g`.
+    fn try_from(segments: Segments) -> std::result::Result<Self, Self::Error> {
+        let Segments {
+            signal_patterns,
+            display_values,
+        } = segments;
+
+        for set in signal_patterns.iter().chain(display_values.iter()) {
+            if set.is_empty() {
+                return Err(String::from("expected at least one segment"));
+            }
+
+            if let Some(segment) = set.iter().find(|c| !('a'..='g').contains(*c)) {
+                return Err(format!("invalid segment {}", segment));
+            }
+        }
+
+        Ok(Self {
+            signal_patterns,
+            display_values,
         })
-        .sum()
+    }
 }
 
-fn sum_displayed_digits(input: Vec<String>) -> usize {
-    input.into_iter().fold(0, |sum, line| {
-        let mut set_mappings = PatternMap::default();
+fn parse_entry(line: &str) -> Result<Entry> {
+    let (signal_patterns, display_values) = line.split_once(" | ").ok_or_else(|| {
+        Error::parse(
+            DayEight::DAY,
+            line,
+            "expected signal patterns and output values separated by ` | `",
+        )
+    })?;
 
-        let (signal_patterns, display_values) = line.split_once(" | ").unwrap();
-        let mut signal_patterns = segments_to_sets(signal_patterns);
-        let display_values = segments_to_sets(display_values);
+    for segments in [signal_patterns, display_values] {
+        if let Some(ix) = segments.find(|c: char| !c.is_whitespace() && !('a'..='g').contains(&c)) {
+            let segment = &segments[ix..];
+            let segment = &segment[..segment.chars().next().map_or(0, char::len_utf8)];
 
-        let [one, seven, four, eight] = find_and_remove_unique_patterns(&mut signal_patterns);
-        set_mappings.insert(&one, '1');
-        set_mappings.insert(&seven, '7');
-        set_mappings.insert(&four, '4');
-        set_mappings.insert(&eight, '8');
+            return Err(Error::parse(DayEight::DAY, segment, "invalid segment")
+                .at_column(column_of(line, segment)));
+        }
+    }
 
-        let filter = HashSet::from_iter(seven.union(&four).into_iter().collect::<String>().chars());
-        let nine = find_and_remove_by_len_and_differences(&mut signal_patterns, 6, &filter, 1);
-        set_mappings.insert(&nine, '9');
+    Ok(Entry {
+        signal_patterns: segments_to_sets(signal_patterns),
+        display_values: segments_to_sets(display_values),
+    })
+}
 
-        let three_ix = signal_patterns
-            .iter()
-            .position(|set| set.len() == 5 && one.difference(&set).count() == 0)
-            .unwrap();
+fn parse_entries<I, S>(lines: I) -> impl Iterator<Item = Result<Entry>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(ix, line)| parse_entry(line.as_ref()).map_err(|e| e.at_line(ix + 1)))
+}
 
-        let three = signal_patterns.remove(three_ix);
-        set_mappings.insert(&three, '3');
+/// Returns the digits shown by the display's output values, or `None` if its signal patterns do
+/// not describe a seven-segment display.
+fn decode_display(entry: &Entry) -> Option<String> {
+    let mut set_mappings = PatternMap::default();
 
-        let two = find_and_remove_by_len_and_differences(&mut signal_patterns, 5, &nine, 1);
-        set_mappings.insert(&two, '2');
+    let mut signal_patterns = entry.signal_patterns.clone();
+    let display_values = &entry.display_values;
 
-        let five = find_and_remove_by_len(&mut signal_patterns, 5);
-        set_mappings.insert(&five, '5');
+    let [one, seven, four, eight] = find_and_remove_unique_patterns(&mut signal_patterns)?;
+    set_mappings.insert(&one, '1');
+    set_mappings.insert(&seven, '7');
+    set_mappings.insert(&four, '4');
+    set_mappings.insert(&eight, '8');
 
-        let six_ix = signal_patterns
-            .iter()
-            .position(|set| five.difference(&set).count() == 0)
-            .unwrap();
+    let filter = BTreeSet::from_iter(seven.union(&four).collect::<String>().chars());
+    let nine = find_and_remove_by_len_and_differences(&mut signal_patterns, 6, &filter, 1)?;
+    set_mappings.insert(&nine, '9');
 
-        let six = signal_patterns.remove(six_ix);
-        set_mappings.insert(&six, '6');
+    let three_ix = signal_patterns
+        .iter()
+        .position(|set| set.len() == 5 && one.difference(set).count() == 0)?;
 
-        let zero = signal_patterns.remove(0);
-        set_mappings.insert(&zero, '0');
+    let three = signal_patterns.remove(three_ix);
+    set_mappings.insert(&three, '3');
 
-        let display_value: usize = display_values
-            .iter()
-            .map(|set| set_mappings.get(set).unwrap())
-            .collect::<String>()
-            .parse()
-            .unwrap_or_default();
+    let two = find_and_remove_by_len_and_differences(&mut signal_patterns, 5, &nine, 1)?;
+    set_mappings.insert(&two, '2');
 
-        sum + display_value
-    })
+    let five = find_and_remove_by_len(&mut signal_patterns, 5)?;
+    set_mappings.insert(&five, '5');
+
+    let six_ix = signal_patterns
+        .iter()
+        .position(|set| five.difference(set).count() == 0)?;
+
+    let six = signal_patterns.remove(six_ix);
+    set_mappings.insert(&six, '6');
+
+    let zero = signal_patterns.pop()?;
+    set_mappings.insert(&zero, '0');
+
+    display_values
+        .iter()
+        .map(|set| set_mappings.get(set))
+        .collect()
+}
+
+/// Only a single entry is held at any time, so `entries` may be streamed.
+pub(crate) fn count_unique_digits(entries: impl IntoIterator<Item = impl Borrow<Entry>>) -> usize {
+    entries
+        .into_iter()
+        .map(|entry| {
+            entry
+                .borrow()
+                .display_values
+                .iter()
+                .filter(|f| f.len() == 2 || f.len() == 3 || f.len() == 4 || f.len() == 7)
+                .count()
+        })
+        .sum()
+}
+
+/// Only a single entry is held at any time, so `entries` may be streamed.
+pub(crate) fn sum_displayed_digits(
+    entries: impl IntoIterator<Item = impl Borrow<Entry>>,
+) -> Result<usize> {
+    entries
+        .into_iter()
+        .enumerate()
+        .try_fold(0usize, |sum, (ix, entry)| {
+            let digits = decode_display(entry.borrow()).ok_or_else(|| {
+                Error::unsolvable(
+                    DayEight::DAY,
+                    format!(
+                        "signal patterns of entry {} do not describe a seven-segment display",
+                        ix + 1
+                    ),
+                )
+            })?;
+
+            let display_value = digits.parse::<usize>().map_err(|_| {
+                Error::unsolvable(
+                    DayEight::DAY,
+                    format!("output value of entry {} is too large", ix + 1),
+                )
+            })?;
+
+            sum.checked_add(display_value).ok_or_else(|| {
+                Error::unsolvable(DayEight::DAY, "the sum of the output values is too large")
+            })
+        })
+}
+
+pub struct DayEight;
+
+impl Solution for DayEight {
+    const DAY: usize = 8;
+
+    type Input = Vec<Entry>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_entries(input.lines()).collect()
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(count_unique_digits(input))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        sum_displayed_digits(input)
+    }
+
+    fn solve_lines(lines: &mut dyn Iterator<Item = String>, part: Part) -> Result<String> {
+        process_results(parse_entries(lines), |entries| match part {
+            Part::One => Ok(count_unique_digits(entries)),
+            Part::Two => sum_displayed_digits(entries),
+        })?
+        .map(|answer| answer.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    fn use_example_input() -> Vec<String> {
+    use crate::day_eight::{DayEight, Entry};
+    use crate::{Error, Result, Solution};
+
+    fn use_example_lines() -> Vec<String> {
         String::from(
             r#"be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
 edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
@@ -146,11 +290,12 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
         .collect()
     }
 
-    fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_eight.txt")
-            .lines()
-            .map(String::from)
-            .collect()
+    fn use_example_input() -> Vec<Entry> {
+        DayEight::parse(&use_example_lines().join("\n")).unwrap()
+    }
+
+    fn use_real_input() -> Vec<Entry> {
+        DayEight::parse(include_str!("../input/default/day_eight.txt")).unwrap()
     }
 
     #[test]
@@ -174,7 +319,7 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
     #[test]
     fn test_sum_displayed_digits_with_example_input() {
         let input = use_example_input();
-        let expected = 61229;
+        let expected = Ok(61229);
         let actual = super::sum_displayed_digits(input);
 
         assert_eq!(expected, actual);
@@ -183,9 +328,105 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
     #[test]
     fn test_sum_displayed_digits_with_real_input() {
         let input = use_real_input();
-        let expected = 998900;
+        let expected = Ok(998900);
+        let actual = super::sum_displayed_digits(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_with_missing_separator() {
+        let input = "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb";
+        let expected: Result<Vec<Entry>> = Err(Error::parse(
+            8,
+            "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb",
+            "expected signal patterns and output values separated by ` | `",
+        ));
+        let actual = DayEight::parse(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_with_invalid_segment() {
+        let mut input = use_example_lines();
+        input[1] = input[1].replace("fcgedb", "fcgexb");
+        let expected: Result<Vec<Entry>> = Err(Error::parse(8, "x", "invalid segment")
+            .at_line(2)
+            .at_column(66));
+        let actual = DayEight::parse(&input.join("\n"));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_sum_displayed_digits_with_undecodable_patterns() {
+        let line =
+            "be be cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe";
+        let input = DayEight::parse(line).unwrap();
+        let expected = Err(Error::unsolvable(
+            8,
+            "signal patterns of entry 1 do not describe a seven-segment display",
+        ));
+        let actual = super::sum_displayed_digits(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    fn use_entry_showing_eights(count: usize) -> String {
+        let mut line = use_example_lines()[0].replace("fdgacbe cefdb cefbgd gcbe", "");
+        line.push_str(&vec!["fdgacbe"; count].join(" "));
+        line
+    }
+
+    #[test]
+    fn test_sum_displayed_digits_with_too_large_output_value() {
+        let input = DayEight::parse(&use_entry_showing_eights(20)).unwrap();
+        let expected = Err(Error::unsolvable(8, "output value of entry 1 is too large"));
+        let actual = super::sum_displayed_digits(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_sum_displayed_digits_with_too_large_sum() {
+        let input = DayEight::parse(&vec![use_entry_showing_eights(19); 3].join("\n")).unwrap();
+        let expected = Err(Error::unsolvable(
+            8,
+            "the sum of the output values is too large",
+        ));
         let actual = super::sum_displayed_digits(input);
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_entry() {
+        let expected = use_example_input().remove(0);
+        let json = serde_json::to_string(&expected).unwrap();
+        let actual = serde_json::from_str::<Entry>(&json).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_entry_with_invalid_segment() {
+        let json = r#"{"signal_patterns": [["a", "x"]], "display_values": [["a"]]}"#;
+        let expected = "invalid segment x";
+        let actual = serde_json::from_str::<Entry>(json).unwrap_err().to_string();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_entry_with_unlit_value() {
+        let json = r#"{"signal_patterns": [["a"]], "display_values": [[]]}"#;
+        let expected = "expected at least one segment";
+        let actual = serde_json::from_str::<Entry>(json).unwrap_err().to_string();
+
+        assert_eq!(expected, actual);
+    }
 }
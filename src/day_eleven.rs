@@ -1,8 +1,13 @@
-use crate::position::Position;
-use std::{convert::TryFrom, ops::Add};
-
-#[derive(Clone, Copy, Default)]
-struct Octopus {
+use crate::{
+    grid::{self, Grid},
+    observer::Observer,
+    Error, Result, Solution,
+};
+use std::{collections::HashSet, convert::TryFrom, fmt, ops::Add};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Octopus {
     energy_level: u32,
     flashed: bool,
 }
@@ -19,17 +24,15 @@ impl Add<u32> for Octopus {
 }
 
 impl TryFrom<char> for Octopus {
-    type Error = ();
-
-    fn try_from(energy_level: char) -> Result<Self, Self::Error> {
-        if let Some(energy_level) = energy_level.to_digit(10) {
-            Ok(Self {
-                flashed: false,
-                energy_level,
-            })
-        } else {
-            Err(())
-        }
+    type Error = Error;
+
+    fn try_from(energy_level: char) -> Result<Self> {
+        let energy_level = grid::digit(DayEleven::DAY, "invalid energy level")(energy_level)?;
+
+        Ok(Self {
+            flashed: false,
+            energy_level,
+        })
     }
 }
 
@@ -43,114 +46,151 @@ impl Octopus {
     }
 }
 
-fn parse_input(input: Vec<String>) -> Vec<Octopus> {
-    input
-        .into_iter()
-        .flat_map(|line| {
-            line.chars()
-                .filter_map(|c| c.try_into().ok())
-                .collect::<Vec<Octopus>>()
-        })
-        .collect()
+fn parse_input(input: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Grid<Octopus>> {
+    Grid::parse(DayEleven::DAY, input, "octopi", Octopus::try_from)
 }
 
-pub fn calculate_flashes(input: Vec<String>, steps: usize) -> usize {
-    let height = input.len();
-    let width = input
-        .get(0)
-        .map(|row| row.chars().count())
-        .unwrap_or_default();
-
-    let (flashes, _) = (0..steps).fold(
-        (0, parse_input(input)),
-        |(mut flashes, mut octopi), _step| {
-            let mut flashed = true;
-
-            octopi = octopi.into_iter().map(|o| o + 1).collect();
-
-            while flashed {
-                flashed = false;
-
-                for ix in 0..octopi.len() {
-                    if octopi[ix].energy_level > 9 && !octopi[ix].flashed {
-                        octopi[ix].flashed = true;
-                        flashes += 1;
-                        flashed = true;
-
-                        Position {
-                            x: ix % width,
-                            y: ix / height,
-                        }
-                        .neighbours_all(height, width)
-                        .into_iter()
-                        .for_each(|position| {
-                            octopi[position.y * height + position.x].energy_level += 1;
-                        });
-                    }
-                }
-            }
+/// The octopi in the cavern as they are between steps, displayed as the energy level of each
+/// octopus, one line per row.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Cavern {
+    octopi: Grid<Octopus>,
+}
 
-            octopi = octopi.into_iter().map(|o| o.reset_if_flashed()).collect();
+impl Cavern {
+    pub fn parse(input: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Self> {
+        Ok(Self {
+            octopi: parse_input(input)?,
+        })
+    }
 
-            (flashes, octopi)
-        },
-    );
+    /// Returns each octopus's energy level, row by row.
+    pub fn energy_levels(&self) -> impl Iterator<Item = u32> + '_ {
+        self.octopi.cells().map(|octopus| octopus.energy_level)
+    }
 
-    flashes
-}
+    /// Raises the energy level of every octopus, letting those with enough energy flash, and
+    /// returns how many of them flashed.
+    fn step(&mut self) -> usize {
+        let mut flashed = true;
+        let mut flashes = 0;
 
-pub fn find_synchronised_flash(input: Vec<String>) -> usize {
-    let height = input.len();
-    let width = input
-        .get(0)
-        .map(|row| row.chars().count())
-        .unwrap_or_default();
+        for octopus in self.octopi.cells_mut() {
+            *octopus = *octopus + 1;
+        }
 
-    let mut octopi = parse_input(input);
+        while flashed {
+            flashed = false;
 
-    (1..usize::MAX)
-        .find(|_step| {
-            let mut flashed = true;
-            let mut flashes = 0;
+            for position in self.octopi.positions() {
+                let octopus = &mut self.octopi[position];
 
-            for ix in 0..octopi.len() {
-                octopi[ix] = octopi[ix] + 1;
-            }
+                if octopus.energy_level > 9 && !octopus.flashed {
+                    octopus.flashed = true;
+                    flashes += 1;
+                    flashed = true;
 
-            while flashed {
-                flashed = false;
-
-                for ix in 0..octopi.len() {
-                    if octopi[ix].energy_level > 9 && !octopi[ix].flashed {
-                        octopi[ix].flashed = true;
-                        flashes += 1;
-                        flashed = true;
-
-                        Position {
-                            x: ix % width,
-                            y: ix / height,
-                        }
-                        .neighbours_all(height, width)
-                        .into_iter()
-                        .for_each(|position| {
-                            octopi[position.y * height + position.x].energy_level += 1;
-                        });
+                    for neighbour in self.octopi.neighbours_all(position) {
+                        self.octopi[neighbour].energy_level += 1;
                     }
                 }
             }
+        }
+
+        for octopus in self.octopi.cells_mut() {
+            *octopus = octopus.reset_if_flashed();
+        }
 
-            for ix in 0..octopi.len() {
-                octopi[ix] = octopi[ix].reset_if_flashed();
+        flashes
+    }
+}
+
+impl fmt::Display for Cavern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (ix, row) in self.octopi.rows().enumerate() {
+            if ix > 0 {
+                writeln!(f)?;
             }
 
-            flashes == octopi.len()
-        })
-        .unwrap_or_default()
+            for octopus in row {
+                write!(f, "{}", octopus.energy_level)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn calculate_flashes(cavern: &Cavern, steps: usize) -> usize {
+    calculate_flashes_with_observer(cavern, steps, &mut ())
+}
+
+/// Counts the flashes over the given number of `steps`, showing `observer` the cavern after each.
+pub fn calculate_flashes_with_observer(
+    cavern: &Cavern,
+    steps: usize,
+    observer: &mut impl Observer<Cavern>,
+) -> usize {
+    let mut cavern = cavern.clone();
+
+    (1..=steps).fold(0, |flashes, step| {
+        let flashes = flashes + cavern.step();
+        observer.observe(step, &cavern);
+
+        flashes
+    })
+}
+
+pub(crate) fn find_synchronised_flash(cavern: &Cavern) -> Result<usize> {
+    let mut cavern = cavern.clone();
+    // Once the energy levels repeat, the octopi are stuck in a cycle in which they never all flash.
+    let mut seen = HashSet::new();
+
+    for step in 1.. {
+        if cavern.step() == cavern.octopi.height() * cavern.octopi.width() {
+            return Ok(step);
+        }
+
+        if !seen.insert(cavern.energy_levels().collect::<Vec<_>>()) {
+            break;
+        }
+    }
+
+    Err(Error::unsolvable(
+        DayEleven::DAY,
+        "octopi never flash together",
+    ))
+}
+
+pub struct DayEleven;
+
+impl Solution for DayEleven {
+    const DAY: usize = 11;
+
+    type Input = Cavern;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Cavern::parse(input.lines())
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(calculate_flashes(input, 100))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        find_synchronised_flash(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    fn use_example_input() -> Vec<String> {
+    use crate::day_eleven::Cavern;
+    use crate::Error;
+
+    fn use_example_lines() -> Vec<String> {
         String::from(
             r#"5483143223
 2745854711
@@ -168,18 +208,19 @@ mod tests {
         .collect()
     }
 
-    fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_eleven.txt")
-            .lines()
-            .map(String::from)
-            .collect()
+    fn use_example_input() -> Cavern {
+        Cavern::parse(use_example_lines()).unwrap()
+    }
+
+    fn use_real_input() -> Cavern {
+        Cavern::parse(include_str!("../input/default/day_eleven.txt").lines()).unwrap()
     }
 
     #[test]
     fn test_calculate_flashes_with_example_input() {
         let input = use_example_input();
         let expected = 1656;
-        let actual = super::calculate_flashes(input, 100);
+        let actual = super::calculate_flashes(&input, 100);
 
         assert_eq!(expected, actual);
     }
@@ -188,7 +229,51 @@ mod tests {
     fn test_calculate_flashes_with_real_input() {
         let input = use_real_input();
         let expected = 1757;
-        let actual = super::calculate_flashes(input, 100);
+        let actual = super::calculate_flashes(&input, 100);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_flashes_with_observer() {
+        let mut actual = vec![];
+        let expected = vec![
+            (
+                1,
+                String::from(
+                    r#"6594254334
+3856965822
+6375667284
+7252447257
+7468496589
+5278635756
+3287952832
+7993992245
+5957959665
+6394862637"#,
+                ),
+            ),
+            (
+                2,
+                String::from(
+                    r#"8807476555
+5089087054
+8597889608
+8485769600
+8700908800
+6600088989
+6800005943
+0000007456
+9000000876
+8700006848"#,
+                ),
+            ),
+        ];
+        super::calculate_flashes_with_observer(
+            &use_example_input(),
+            2,
+            &mut |step, cavern: &Cavern| actual.push((step, cavern.to_string())),
+        );
 
         assert_eq!(expected, actual);
     }
@@ -196,8 +281,8 @@ mod tests {
     #[test]
     fn test_find_synchronised_flash_with_example_input() {
         let input = use_example_input();
-        let expected = 195;
-        let actual = super::find_synchronised_flash(input);
+        let expected = Ok(195);
+        let actual = super::find_synchronised_flash(&input);
 
         assert_eq!(expected, actual);
     }
@@ -205,8 +290,60 @@ mod tests {
     #[test]
     fn test_find_synchronised_flash_with_real_input() {
         let input = use_real_input();
-        let expected = 422;
-        let actual = super::find_synchronised_flash(input);
+        let expected = Ok(422);
+        let actual = super::find_synchronised_flash(&input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_with_invalid_energy_level() {
+        let mut input = use_example_lines();
+        input[6] = String::from("21768417-1");
+        let expected = Err(Error::parse(11, '-', "invalid energy level")
+            .at_line(7)
+            .at_column(9));
+        let actual = Cavern::parse(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_with_ragged_rows() {
+        let mut input = use_example_lines();
+        input[9] = String::from("52837515261");
+        let expected = Err(Error::parse(11, "52837515261", "expected 10 octopi").at_line(10));
+        let actual = Cavern::parse(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_find_synchronised_flash_when_octopi_never_flash_together() {
+        let input = Cavern::parse(["573", "235", "030"]).unwrap();
+        let expected = Err(Error::unsolvable(11, "octopi never flash together"));
+        let actual = super::find_synchronised_flash(&input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serialise_cavern() {
+        let cavern = Cavern::parse(["12", "90"]).unwrap();
+        let expected = serde_json::json!({
+            "octopi": {
+                "cells": [
+                    {"energy_level": 1, "flashed": false},
+                    {"energy_level": 2, "flashed": false},
+                    {"energy_level": 9, "flashed": false},
+                    {"energy_level": 0, "flashed": false},
+                ],
+                "height": 2,
+                "width": 2,
+            },
+        });
+        let actual = serde_json::to_value(&cavern).unwrap();
 
         assert_eq!(expected, actual);
     }
@@ -1,24 +1,43 @@
-use crate::position::Position;
+use crate::{
+    grid::{self, Grid},
+    position::Position,
+    Error, Result, Solution,
+};
 use std::collections::HashMap;
 
-fn generate_part_two_input(input: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+/// Parses the risk level of each position in the cave, row by row.
+pub fn parse_input(input: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Grid<usize>> {
+    let decode = grid::digit(DayFifteen::DAY, "invalid risk level");
+
+    let map = Grid::parse(DayFifteen::DAY, input, "risk levels", decode)?;
+
+    if map.height() == 0 || map.width() == 0 {
+        return Err(Error::unsolvable(DayFifteen::DAY, "there is no risk map"));
+    }
+
+    Ok(map)
+}
+
+/// Tiles the risk map five times in each direction, raising the risk levels of each tile by its
+/// distance from the first.
+pub fn generate_part_two_input(input: &Grid<usize>) -> Grid<usize> {
     let repeat = 5;
-    let height = input.len();
-    let width = input.get(0).map(|row| row.len()).unwrap_or_default();
+    let (height, width) = (input.height(), input.width());
 
-    let mut output = vec![vec![0; width * repeat]; height * repeat];
+    let mut output = Grid::new(height * repeat, width * repeat, 0);
 
     for y in 0..repeat {
         for x in 0..repeat {
-            for row in 0..height {
-                for col in 0..width {
-                    let mut value = input[row][col] + y + x;
-                    if value > 9 {
-                        value = value % 9;
-                    }
-
-                    output[y * height + row][x * width + col] = value;
+            for (position, risk) in input.iter() {
+                let mut value = risk + y + x;
+                if value > 9 {
+                    value %= 9;
                 }
+
+                output[Position {
+                    x: x * width + position.x,
+                    y: y * height + position.y,
+                }] = value;
             }
         }
     }
@@ -44,14 +63,7 @@ fn reconstruct_path(
     path
 }
 
-fn astar<H>(
-    start: Position,
-    goal: Position,
-    heuristic: H,
-    height: usize,
-    width: usize,
-    distances: &Vec<Vec<usize>>,
-) -> Vec<Position>
+fn astar<H>(start: Position, goal: Position, heuristic: H, distances: &Grid<usize>) -> Vec<Position>
 where
     H: Fn(Position, Position) -> usize,
 {
@@ -68,7 +80,7 @@ where
     while !open_set.is_empty() {
         let current = open_set
             .iter()
-            .map(|node| (node, f_score.get(&node).map(|f| *f).unwrap_or(usize::MAX)))
+            .map(|node| (node, f_score.get(node).copied().unwrap_or(usize::MAX)))
             .min_by_key(|(_node, f)| *f)
             .map(|(node, _f)| *node)
             .unwrap();
@@ -80,10 +92,10 @@ where
         let current_ix = open_set.iter().position(|p| *p == current).unwrap();
         open_set.remove(current_ix);
 
-        for neighbour in current.neighbours_orthogonal(height, width) {
-            let tentative_gscore = g_score[&current] + distances[neighbour.y][neighbour.x];
+        for neighbour in distances.neighbours_orthogonal(current) {
+            let tentative_gscore = g_score[&current] + distances[neighbour];
 
-            if tentative_gscore < g_score.get(&neighbour).map(|g| *g).unwrap_or(usize::MAX) {
+            if tentative_gscore < g_score.get(&neighbour).copied().unwrap_or(usize::MAX) {
                 came_from.insert(neighbour, current);
                 g_score.insert(neighbour, tentative_gscore);
                 f_score.insert(neighbour, tentative_gscore + heuristic(neighbour, goal));
@@ -98,25 +110,47 @@ where
     path
 }
 
-fn calculate_minimum_total_risk(input: Vec<Vec<usize>>) -> usize {
-    let height = input.len();
-    let width = input.get(0).map(|row| row.len()).unwrap_or_default();
-
+pub(crate) fn calculate_minimum_total_risk(input: &Grid<usize>) -> usize {
     let start = Position::default();
     let goal = Position {
-        x: width - 1,
-        y: height - 1,
+        x: input.width() - 1,
+        y: input.height() - 1,
     };
 
-    let path = astar(start, goal, orthogonal_distance, height, width, &input);
-    let path_risk: usize = path.into_iter().map(|Position { x, y }| input[y][x]).sum();
+    let path = astar(start, goal, orthogonal_distance, input);
+    let path_risk: usize = path.into_iter().map(|position| input[position]).sum();
 
-    path_risk - input[0][0]
+    path_risk - input[start]
+}
+
+pub struct DayFifteen;
+
+impl Solution for DayFifteen {
+    const DAY: usize = 15;
+
+    type Input = Grid<usize>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input.lines())
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(calculate_minimum_total_risk(input))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(calculate_minimum_total_risk(&generate_part_two_input(
+            input,
+        )))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day_fifteen::generate_part_two_input;
+    use crate::day_fifteen::{generate_part_two_input, parse_input};
+    use crate::Error;
 
     fn use_part_one_example_input() -> Vec<String> {
         String::from(
@@ -195,30 +229,18 @@ mod tests {
     }
 
     fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_fifteen.txt")
+        include_str!("../input/default/day_fifteen.txt")
             .lines()
             .map(String::from)
             .collect()
     }
 
-    fn parse_input(input: Vec<String>) -> Vec<Vec<usize>> {
-        input
-            .into_iter()
-            .map(|row| {
-                row.chars()
-                    .filter_map(|c| c.to_digit(10))
-                    .map(|d| d as usize)
-                    .collect()
-            })
-            .collect()
-    }
-
     #[test]
     fn test_calculate_minimum_total_risk_with_part_one_example_input() {
         let input = use_part_one_example_input();
-        let input = parse_input(input);
+        let input = parse_input(input).unwrap();
         let expected = 40;
-        let actual = super::calculate_minimum_total_risk(input);
+        let actual = super::calculate_minimum_total_risk(&input);
 
         assert_eq!(expected, actual);
     }
@@ -226,9 +248,9 @@ mod tests {
     #[test]
     fn test_calculate_minimum_total_risk_with_real_input() {
         let input = use_real_input();
-        let input = parse_input(input);
+        let input = parse_input(input).unwrap();
         let expected = 386;
-        let actual = super::calculate_minimum_total_risk(input);
+        let actual = super::calculate_minimum_total_risk(&input);
 
         assert_eq!(expected, actual);
     }
@@ -236,10 +258,10 @@ mod tests {
     #[test]
     fn test_generate_part_two_input() {
         let input = use_part_one_example_input();
-        let input = parse_input(input);
+        let input = parse_input(input).unwrap();
         let expected = use_part_two_example_input();
-        let expected = parse_input(expected);
-        let actual = super::generate_part_two_input(input);
+        let expected = parse_input(expected).unwrap();
+        let actual = super::generate_part_two_input(&input);
 
         assert_eq!(expected, actual);
     }
@@ -247,9 +269,9 @@ mod tests {
     #[test]
     fn test_calculate_minimum_total_risk_with_part_two_example_input() {
         let input = use_part_two_example_input();
-        let input = parse_input(input);
+        let input = parse_input(input).unwrap();
         let expected = 315;
-        let actual = super::calculate_minimum_total_risk(input);
+        let actual = super::calculate_minimum_total_risk(&input);
 
         assert_eq!(expected, actual);
     }
@@ -257,10 +279,30 @@ mod tests {
     #[test]
     fn test_calculate_minimum_total_risk_with_generated_part_two_real_input() {
         let input = use_real_input();
-        let input = parse_input(input);
-        let input = generate_part_two_input(input);
+        let input = parse_input(input).unwrap();
+        let input = generate_part_two_input(&input);
         let expected = 2806;
-        let actual = super::calculate_minimum_total_risk(input);
+        let actual = super::calculate_minimum_total_risk(&input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_invalid_risk_level() {
+        let mut input = use_part_one_example_input();
+        input[4] = String::from("74634 7111");
+        let expected = Err(Error::parse(15, ' ', "invalid risk level")
+            .at_line(5)
+            .at_column(6));
+        let actual = parse_input(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_without_risk_levels() {
+        let expected = Err(Error::unsolvable(15, "there is no risk map"));
+        let actual = parse_input(Vec::<String>::new());
 
         assert_eq!(expected, actual);
     }
@@ -1,61 +1,64 @@
-use std::{cmp::Ordering, collections::HashMap, hash::Hash};
-
-trait Point: Copy + Eq + PartialEq + Sized {
+use crate::{
+    error::column_of,
+    position::{Coordinate, Position},
+    process_results, Error, Part, Result, Solution,
+};
+use std::{borrow::Borrow, cmp::Ordering, collections::HashMap, convert::TryFrom, hash::Hash};
+
+/// The largest coordinate accepted, so that a single line of vents cannot cover more points than
+/// fit in memory.
+const MAX_COORDINATE: usize = 9999;
+
+pub(crate) trait Point: Copy + Eq + PartialEq + Sized {
     fn new(x: usize, y: usize) -> Self;
 
     fn x(&self) -> usize;
 
     fn y(&self) -> usize;
 
-    fn from_coords(coords: &str) -> Result<Self, ()> {
-        let (x, y) = coords.split_once(',').unwrap_or_default();
-
-        match (x.parse(), y.parse()) {
-            (Ok(x), Ok(y)) => Ok(Self::new(x, y)),
-            _ => Err(()),
-        }
-    }
-
     fn calculate_scalar(self, other: Self) -> Option<Vec<Self>> {
         match (self.x().cmp(&other.x()), self.y().cmp(&other.y())) {
             (Ordering::Equal, Ordering::Equal) => Some(vec![self]),
-            (Ordering::Equal, Ordering::Greater | Ordering::Less) => Some(vertical_scalar(self, other)),
-            (Ordering::Greater | Ordering::Less, Ordering::Equal) => Some(horizontal_scalar(self, other)),
-            _ => Some(diagonal_scalar(self, other)),
+            (Ordering::Equal, Ordering::Greater | Ordering::Less) => {
+                Some(vertical_scalar(self, other))
+            }
+            (Ordering::Greater | Ordering::Less, Ordering::Equal) => {
+                Some(horizontal_scalar(self, other))
+            }
+            _ => diagonal_scalar(self, other),
         }
     }
 }
 
-fn diagonal_scalar<P>(a: P, b: P) -> Vec<P> where P: Point {
-    let mut current = a;
-    let mut scalar = vec![];
-
-    let x_step: isize = match a.x().cmp(&b.x()) {
-        Ordering::Equal => 0,
-        Ordering::Greater => -1,
-        Ordering::Less => 1,
-    };
-
-    let y_step: isize = match a.y().cmp(&b.y()) {
-        Ordering::Equal => 0,
-        Ordering::Greater => -1,
-        Ordering::Less => 1,
+/// Walks the diagonal from `a` to `b` as signed coordinates, or returns `None` if either end is
+/// too large to be one.
+fn diagonal_scalar<P>(a: P, b: P) -> Option<Vec<P>>
+where
+    P: Point,
+{
+    let to_coordinate = |p: P| Coordinate::try_from(Position { x: p.x(), y: p.y() }).ok();
+    let from_coordinate = |c: Coordinate| {
+        Position::try_from(c)
+            .ok()
+            .map(|Position { x, y }| P::new(x, y))
     };
 
-    while current != b {
-        let next_x = current.x() as isize + x_step;
-        let next_y = current.y() as isize + y_step;
-        scalar.push(current);
+    let (mut current, end) = (to_coordinate(a)?, to_coordinate(b)?);
+    let step = (end - current).signum();
+    let mut scalar = vec![from_coordinate(current)?];
 
-        current = P::new(next_x as usize, next_y as usize);
+    while current != end {
+        current = current + step;
+        scalar.push(from_coordinate(current)?);
     }
 
-    scalar.push(current);
-
-    scalar
+    Some(scalar)
 }
 
-fn horizontal_scalar<P>(a: P, b: P) -> Vec<P> where P: Point {
+fn horizontal_scalar<P>(a: P, b: P) -> Vec<P>
+where
+    P: Point,
+{
     if a.x() > b.x() {
         (b.x()..=a.x()).map(|x| P::new(x, a.y())).collect()
     } else {
@@ -63,7 +66,10 @@ fn horizontal_scalar<P>(a: P, b: P) -> Vec<P> where P: Point {
     }
 }
 
-fn vertical_scalar<P>(a: P, b: P) -> Vec<P> where P: Point {
+fn vertical_scalar<P>(a: P, b: P) -> Vec<P>
+where
+    P: Point,
+{
     if a.y() > b.y() {
         (b.y()..=a.y()).map(|y| P::new(a.x(), y)).collect()
     } else {
@@ -72,7 +78,7 @@ fn vertical_scalar<P>(a: P, b: P) -> Vec<P> where P: Point {
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct OrthogonalPoint {
+pub(crate) struct OrthogonalPoint {
     x: usize,
     y: usize,
 }
@@ -93,15 +99,19 @@ impl Point for OrthogonalPoint {
     fn calculate_scalar(self, other: Self) -> Option<Vec<Self>> {
         match (self.x().cmp(&other.x()), self.y().cmp(&other.y())) {
             (Ordering::Equal, Ordering::Equal) => Some(vec![self]),
-            (Ordering::Equal, Ordering::Greater | Ordering::Less) => Some(vertical_scalar(self, other)),
-            (Ordering::Greater | Ordering::Less, Ordering::Equal) => Some(horizontal_scalar(self, other)),
+            (Ordering::Equal, Ordering::Greater | Ordering::Less) => {
+                Some(vertical_scalar(self, other))
+            }
+            (Ordering::Greater | Ordering::Less, Ordering::Equal) => {
+                Some(horizontal_scalar(self, other))
+            }
             _ => None, // effectively remove diagonal scalar implementation for orthogonal point
         }
     }
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct DiagonalPoint {
+pub(crate) struct DiagonalPoint {
     x: usize,
     y: usize,
 }
@@ -120,38 +130,124 @@ impl Point for DiagonalPoint {
     }
 }
 
-fn calculate_overlapping_points<P>(input: Vec<String>) -> usize where P: Point + Eq + Hash {
-    input
-        .iter()
-        .fold(
-            HashMap::with_capacity(input.len()),
-            |mut frequencies, line| {
-                let (a, b) = line.split_once(" -> ").unwrap_or_default();
-
-                match (P::from_coords(a), P::from_coords(b)) {
-                    (Ok(a), Ok(b)) => {
-                        let scalar = a.calculate_scalar(b).unwrap_or_default();
-                        scalar.into_iter().for_each(|point| {
-                            let frequency = frequencies.entry(point).or_insert(0);
-                            *frequency += 1;
-                        });
-                    }
-                    _ => {}
-                }
-
-                frequencies
-            },
-        )
+/// A line of vents, from one end to the other.
+type Line = (Position, Position);
+
+fn parse_position(coords: &str) -> Result<Position> {
+    let (x, y) = coords
+        .split_once(',')
+        .ok_or_else(|| Error::parse(DayFive::DAY, coords, "expected coordinates `x,y`"))?;
+
+    let parse_coordinate = |coordinate: &str| match coordinate.parse() {
+        Ok(coordinate) if coordinate <= MAX_COORDINATE => Ok(coordinate),
+        Ok(_) => Err(Error::parse(
+            DayFive::DAY,
+            coordinate,
+            "coordinate is too large",
+        )),
+        Err(_) => Err(Error::parse(DayFive::DAY, coordinate, "invalid coordinate")),
+    };
+
+    let x = parse_coordinate(x).map_err(|e| e.at_column(1))?;
+    let y = parse_coordinate(y).map_err(|e| e.at_column(column_of(coords, y)))?;
+
+    Ok(Position { x, y })
+}
+
+fn parse_line(line: &str) -> Result<Line> {
+    let (a, b) = line
+        .split_once(" -> ")
+        .ok_or_else(|| Error::parse(DayFive::DAY, line, "expected a line `x1,y1 -> x2,y2`"))?;
+
+    let a = parse_position(a).map_err(|e| e.at_column(column_of(line, a)))?;
+    let b = parse_position(b).map_err(|e| e.at_column(column_of(line, b)))?;
+
+    if a.x != b.x && a.y != b.y && a.x.abs_diff(b.x) != a.y.abs_diff(b.y) {
+        return Err(Error::parse(
+            DayFive::DAY,
+            line,
+            "line is not horizontal, vertical or diagonal",
+        ));
+    }
+
+    Ok((a, b))
+}
+
+fn parse_lines<I, S>(lines: I) -> impl Iterator<Item = Result<Line>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(ix, line)| parse_line(line.as_ref()).map_err(|e| e.at_line(ix + 1)))
+}
+
+/// Only the frequency of each covered point is kept, so `lines` may be streamed.
+pub(crate) fn calculate_overlapping_points<P>(
+    lines: impl IntoIterator<Item = impl Borrow<Line>>,
+) -> usize
+where
+    P: Point + Eq + Hash,
+{
+    let mut frequencies = HashMap::new();
+
+    for line in lines {
+        let (a, b) = *line.borrow();
+        let scalar = P::new(a.x, a.y)
+            .calculate_scalar(P::new(b.x, b.y))
+            .unwrap_or_default();
+
+        scalar.into_iter().for_each(|point| {
+            let frequency = frequencies.entry(point).or_insert(0);
+            *frequency += 1;
+        });
+    }
+
+    frequencies
         .values()
         .filter(|frequency| **frequency > 1)
         .count()
 }
 
+pub struct DayFive;
+
+impl Solution for DayFive {
+    const DAY: usize = 5;
+
+    type Input = Vec<Line>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input.lines()).collect()
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(calculate_overlapping_points::<OrthogonalPoint>(input))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(calculate_overlapping_points::<DiagonalPoint>(input))
+    }
+
+    fn solve_lines(lines: &mut dyn Iterator<Item = String>, part: Part) -> Result<String> {
+        let answer = process_results(parse_lines(lines), |lines| match part {
+            Part::One => calculate_overlapping_points::<OrthogonalPoint>(lines),
+            Part::Two => calculate_overlapping_points::<DiagonalPoint>(lines),
+        })?;
+
+        Ok(answer.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::day_five::{DiagonalPoint, OrthogonalPoint};
+    use crate::day_five::{DayFive, DiagonalPoint, Line, OrthogonalPoint};
+    use crate::{Error, Result, Solution};
 
-    fn use_example_input() -> Vec<String> {
+    fn use_example_lines() -> Vec<String> {
         String::from(
             r#"0,9 -> 5,9
 8,0 -> 0,8
@@ -169,11 +265,12 @@ mod tests {
         .collect()
     }
 
-    fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_five.txt")
-            .lines()
-            .map(String::from)
-            .collect()
+    fn use_example_input() -> Vec<Line> {
+        DayFive::parse(&use_example_lines().join("\n")).unwrap()
+    }
+
+    fn use_real_input() -> Vec<Line> {
+        DayFive::parse(include_str!("../input/default/day_five.txt")).unwrap()
     }
 
     #[test]
@@ -211,4 +308,44 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_parse_with_invalid_coordinate() {
+        let mut input = use_example_lines();
+        input[2] = String::from("9,4 -> 3,y");
+        let expected: Result<Vec<Line>> = Err(Error::parse(5, "y", "invalid coordinate")
+            .at_line(3)
+            .at_column(10));
+        let actual = DayFive::parse(&input.join("\n"));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_with_coordinate_too_large() {
+        let mut input = use_example_lines();
+        input[2] = String::from("9,4 -> 1000000000,4");
+        let expected: Result<Vec<Line>> =
+            Err(Error::parse(5, "1000000000", "coordinate is too large")
+                .at_line(3)
+                .at_column(8));
+        let actual = DayFive::parse(&input.join("\n"));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_with_skewed_line() {
+        let mut input = use_example_lines();
+        input[1] = String::from("8,0 -> 0,9");
+        let expected: Result<Vec<Line>> = Err(Error::parse(
+            5,
+            "8,0 -> 0,9",
+            "line is not horizontal, vertical or diagonal",
+        )
+        .at_line(2));
+        let actual = DayFive::parse(&input.join("\n"));
+
+        assert_eq!(expected, actual);
+    }
 }
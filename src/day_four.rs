@@ -1,26 +1,104 @@
+use crate::{error::column_of, Error, Result, Solution};
 use std::collections::HashMap;
 
-#[derive(Debug)]
-struct BingoCard {
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(try_from = "Marks")
+)]
+pub struct BingoCard {
     column_bingo: bool,
     columns: Vec<HashMap<usize, bool>>,
     row_bingo: bool,
     rows: Vec<HashMap<usize, bool>>,
 }
 
-impl BingoCard {
-    fn new(lines: Vec<String>) -> Self {
-        let matrix: Vec<Vec<usize>> = lines
-            .into_iter()
-            .map(|line| {
-                line.split_whitespace()
-                    .filter_map(|n| n.parse::<usize>().ok())
-                    .collect()
-            })
-            .collect();
+/// The fields of a deserialised [`BingoCard`], before they are checked to make a card.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Marks {
+    column_bingo: bool,
+    columns: Vec<HashMap<usize, bool>>,
+    row_bingo: bool,
+    rows: Vec<HashMap<usize, bool>>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Marks> for BingoCard {
+    type Error = String;
+
+    /// Accepts the marks only if they could have come from [`parse_input`] and marking numbers:
+    /// five rows of a grid whose rows and columns hold the same marked and unmarked numbers, with
+    /// bingo called only for a completely marked row or column.
+    fn try_from(marks: Marks) -> std::result::Result<Self, Self::Error> {
+        use std::collections::BTreeSet;
+
+        let Marks {
+            column_bingo,
+            columns,
+            row_bingo,
+            rows,
+        } = marks;
+
+        if rows.len() != 5 {
+            return Err(format!("expected 5 rows, found {}", rows.len()));
+        }
+
+        if rows
+            .iter()
+            .chain(columns.iter())
+            .any(|scalar| scalar.is_empty())
+            || rows.iter().any(|row| row.len() > columns.len())
+            || columns.iter().any(|column| column.len() > rows.len())
+        {
+            return Err(String::from("rows and columns do not make a grid"));
+        }
+
+        let numbers = |matrix: &[HashMap<usize, bool>]| -> BTreeSet<(usize, bool)> {
+            matrix
+                .iter()
+                .flat_map(|scalar| scalar.iter().map(|(&number, &marked)| (number, marked)))
+                .collect()
+        };
+
+        if numbers(&rows) != numbers(&columns) {
+            return Err(String::from(
+                "rows and columns do not hold the same numbers",
+            ));
+        }
+
+        let complete = |matrix: &[HashMap<usize, bool>]| {
+            matrix
+                .iter()
+                .any(|scalar| scalar.values().all(|marked| *marked))
+        };
+
+        if (column_bingo && !complete(&columns)) || (row_bingo && !complete(&rows)) {
+            return Err(String::from(
+                "bingo is called without a complete row or column",
+            ));
+        }
 
+        Ok(Self {
+            column_bingo,
+            columns,
+            row_bingo,
+            rows,
+        })
+    }
+}
+
+impl BingoCard {
+    /// Creates an unmarked card from its rows, or returns `None` unless there are five rows all as
+    /// long as the first.
+    pub(crate) fn new(matrix: Vec<Vec<usize>>) -> Option<Self> {
         let height = matrix.len();
-        let width = matrix.get(0).map(|row| row.len()).unwrap_or_default();
+        let width = matrix.first().map(|row| row.len()).unwrap_or_default();
+
+        if height != 5 || matrix.iter().any(|row| row.len() != width) {
+            return None;
+        }
 
         let mut columns = vec![HashMap::with_capacity(height); width];
         let mut rows = vec![HashMap::with_capacity(width); height];
@@ -32,15 +110,15 @@ impl BingoCard {
             }
         }
 
-        Self {
+        Some(Self {
             column_bingo: false,
             row_bingo: false,
             columns,
             rows,
-        }
+        })
     }
 
-    fn mark(&mut self, number: usize) -> Option<usize> {
+    fn mark(&mut self, number: usize) -> Option<Result<usize>> {
         self.columns.iter_mut().for_each(|column| {
             if self.column_bingo || self.row_bingo {
                 return;
@@ -72,49 +150,97 @@ impl BingoCard {
         }
     }
 
-    fn scalar_score(scalar: &HashMap<usize, bool>) -> usize {
+    fn scalar_score(scalar: &HashMap<usize, bool>) -> Option<usize> {
         scalar
             .keys()
-            .filter(|k| !scalar.get(&k).map(|v| *v).unwrap_or_default())
-            .sum::<usize>()
+            .filter(|k| !scalar.get(k).copied().unwrap_or_default())
+            .try_fold(0usize, |sum, k| sum.checked_add(*k))
     }
 
-    fn score(matrix: &Vec<HashMap<usize, bool>>, number: usize) -> usize {
-        matrix.iter().map(Self::scalar_score).sum::<usize>() * number
+    fn score(matrix: &[HashMap<usize, bool>], number: usize) -> Result<usize> {
+        matrix
+            .iter()
+            .map(Self::scalar_score)
+            .try_fold(0usize, |sum, score| sum.checked_add(score?))
+            .and_then(|sum| sum.checked_mul(number))
+            .ok_or_else(|| Error::unsolvable(DayFour::DAY, "the score is too large"))
     }
 }
 
-fn parse_input(mut input: Vec<String>) -> (Vec<usize>, Vec<BingoCard>) {
+fn parse_number(line: &str, number: &str) -> Result<usize> {
+    number.parse().map_err(|_| {
+        Error::parse(DayFour::DAY, number, "invalid number").at_column(column_of(line, number))
+    })
+}
+
+/// Parses the numbers drawn, in order, and the bingo cards they are marked on.
+pub fn parse_input(
+    input: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Result<(Vec<usize>, Vec<BingoCard>)> {
+    let input: Vec<_> = input.into_iter().collect();
     let calls = input
-        .remove(0)
+        .first()
+        .map(AsRef::as_ref)
+        .ok_or_else(|| Error::unsolvable(DayFour::DAY, "no numbers are drawn"))?;
+
+    let calls = calls
         .split(',')
-        .filter_map(|n| n.parse().ok())
-        .collect();
+        .map(|number| parse_number(calls, number))
+        .collect::<Result<_>>()?;
+
+    let rows = input
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(ix, line)| (ix, line.as_ref()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(ix, line)| {
+            line.split_whitespace()
+                .map(|number| parse_number(line, number))
+                .collect::<Result<Vec<usize>>>()
+                .map(|row| (ix + 1, line, row))
+                .map_err(|e| e.at_line(ix + 1))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let cards = rows
+        .chunks(5)
+        .map(|rows| {
+            let (_, _, first) = &rows[0];
 
-    let lines: Vec<String> = input.into_iter().filter(|l| !l.is_empty()).collect();
+            for (line, text, row) in rows {
+                if row.len() != first.len() {
+                    let reason = format!("expected {} numbers in row", first.len());
+                    return Err(Error::parse(DayFour::DAY, *text, reason).at_line(*line));
+                }
+            }
 
-    let cards = lines
-        .chunks(5)
-        .map(|lines| BingoCard::new(lines.to_vec()))
-        .collect();
+            BingoCard::new(rows.iter().map(|(_, _, row)| row.clone()).collect()).ok_or_else(|| {
+                let (line, text, _) = &rows[rows.len() - 1];
+                let reason = "incomplete card, expected 5 rows";
+                Error::parse(DayFour::DAY, *text, reason).at_line(*line)
+            })
+        })
+        .collect::<Result<_>>()?;
 
-    (calls, cards)
+    Ok((calls, cards))
 }
 
-fn calculate_first_winning_score(input: Vec<String>) -> usize {
-    let (calls, mut cards) = parse_input(input);
+pub(crate) fn calculate_first_winning_score(calls: &[usize], cards: &[BingoCard]) -> Result<usize> {
+    let mut cards = cards.to_vec();
 
     calls
-        .into_iter()
+        .iter()
+        .copied()
         .find_map(|number| cards.iter_mut().find_map(|card| card.mark(number)))
-        .unwrap_or_default()
+        .unwrap_or_else(|| Err(Error::unsolvable(DayFour::DAY, "no card wins")))
 }
 
-fn calculate_last_winning_score(input: Vec<String>) -> usize {
-    let (calls, mut cards) = parse_input(input);
+pub(crate) fn calculate_last_winning_score(calls: &[usize], cards: &[BingoCard]) -> Result<usize> {
+    let mut cards = cards.to_vec();
     let mut score = None;
 
-    for number in calls {
+    for &number in calls {
         cards = cards
             .into_iter()
             .filter_map(|mut card| {
@@ -132,12 +258,36 @@ fn calculate_last_winning_score(input: Vec<String>) -> usize {
         }
     }
 
-    score.unwrap_or_default()
+    score.unwrap_or_else(|| Err(Error::unsolvable(DayFour::DAY, "no card wins")))
+}
+
+pub struct DayFour;
+
+impl Solution for DayFour {
+    const DAY: usize = 4;
+
+    type Input = (Vec<usize>, Vec<BingoCard>);
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input.lines())
+    }
+
+    fn part_one((calls, cards): &Self::Input) -> Result<Self::PartOne> {
+        calculate_first_winning_score(calls, cards)
+    }
+
+    fn part_two((calls, cards): &Self::Input) -> Result<Self::PartTwo> {
+        calculate_last_winning_score(calls, cards)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    fn use_example_input() -> Vec<String> {
+    use crate::Error;
+
+    fn use_example_lines() -> Vec<String> {
         String::from(
             r#"7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
 
@@ -164,45 +314,144 @@ mod tests {
         .collect()
     }
 
-    fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_four.txt")
-            .lines()
-            .map(String::from)
-            .collect()
+    fn use_example_input() -> (Vec<usize>, Vec<super::BingoCard>) {
+        super::parse_input(use_example_lines()).unwrap()
+    }
+
+    fn use_real_input() -> (Vec<usize>, Vec<super::BingoCard>) {
+        super::parse_input(include_str!("../input/default/day_four.txt").lines()).unwrap()
     }
 
     #[test]
     fn test_calculate_first_winning_score_with_example_input() {
-        let input = use_example_input();
-        let expected = 4512;
-        let actual = super::calculate_first_winning_score(input);
+        let (calls, cards) = use_example_input();
+        let expected = Ok(4512);
+        let actual = super::calculate_first_winning_score(&calls, &cards);
 
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn test_calculate_first_winning_score_with_real_input() {
-        let input = use_real_input();
-        let expected = 41668;
-        let actual = super::calculate_first_winning_score(input);
+        let (calls, cards) = use_real_input();
+        let expected = Ok(41668);
+        let actual = super::calculate_first_winning_score(&calls, &cards);
 
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn test_calculate_last_winning_score_with_example_input() {
-        let input = use_example_input();
-        let expected = 1924;
-        let actual = super::calculate_last_winning_score(input);
+        let (calls, cards) = use_example_input();
+        let expected = Ok(1924);
+        let actual = super::calculate_last_winning_score(&calls, &cards);
 
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn test_calculate_last_with_real_input() {
-        let input = use_real_input();
-        let expected = 10478;
-        let actual = super::calculate_last_winning_score(input);
+        let (calls, cards) = use_real_input();
+        let expected = Ok(10478);
+        let actual = super::calculate_last_winning_score(&calls, &cards);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_first_winning_score_too_large() {
+        let (calls, cards) = super::parse_input(
+            r#"6,7,8,9,10
+
+18446744073709551615 18446744073709551615 3 4 5
+ 6  7  8  9 10
+11 12 13 14 15
+16 17 18 19 20
+21 22 23 24 25"#
+                .lines(),
+        )
+        .unwrap();
+        let expected = Err(Error::unsolvable(4, "the score is too large"));
+        let actual = super::calculate_first_winning_score(&calls, &cards);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_invalid_number() {
+        let mut input = use_example_lines();
+        input[4] = String::from(" 8  2 23  x 24");
+        let expected = Err(Error::parse(4, "x", "invalid number")
+            .at_line(5)
+            .at_column(11));
+        let actual = super::parse_input(input).map(|_| ());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_incomplete_card() {
+        let mut input = use_example_lines();
+        input.truncate(18);
+        let expected =
+            Err(Error::parse(4, "22 11 13  6  5", "incomplete card, expected 5 rows").at_line(18));
+        let actual = super::parse_input(input).map(|_| ());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serialise_bingo_cards() {
+        let (_, cards) = use_example_input();
+        let json = serde_json::to_string(&cards).unwrap();
+
+        let expected = serde_json::to_value(&cards).unwrap();
+        let actual =
+            serde_json::to_value(serde_json::from_str::<Vec<super::BingoCard>>(&json).unwrap())
+                .unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_bingo_card_with_missing_row() {
+        let (_, cards) = use_example_input();
+        let mut json = serde_json::to_value(&cards[0]).unwrap();
+        json["rows"].as_array_mut().unwrap().pop();
+        let expected = "expected 5 rows, found 4";
+        let actual = serde_json::from_value::<super::BingoCard>(json)
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_bingo_card_with_different_numbers() {
+        let (_, cards) = use_example_input();
+        let mut json = serde_json::to_value(&cards[0]).unwrap();
+        json["columns"][0] = serde_json::json!({"99": false});
+        let expected = "rows and columns do not hold the same numbers";
+        let actual = serde_json::from_value::<super::BingoCard>(json)
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_bingo_card_with_false_bingo() {
+        let (_, cards) = use_example_input();
+        let mut json = serde_json::to_value(&cards[0]).unwrap();
+        json["row_bingo"] = serde_json::json!(true);
+        let expected = "bingo is called without a complete row or column";
+        let actual = serde_json::from_value::<super::BingoCard>(json)
+            .unwrap_err()
+            .to_string();
 
         assert_eq!(expected, actual);
     }
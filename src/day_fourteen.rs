@@ -1,19 +1,71 @@
+use crate::{error::column_of, observer::Observer, Error, Result, Solution};
 use std::collections::HashMap;
 
-fn calculate_frequency_range(mut input: Vec<String>, steps: usize) -> usize {
-    let template: Vec<char> = input.remove(0).chars().collect();
-    let rules: HashMap<(char, char), char> =
-        input.into_iter().fold(HashMap::new(), |mut rules, rule| {
-            let (input, output) = rule.split_once(" -> ").unwrap_or_default();
-            let mut input: Vec<char> = input.chars().collect();
+fn parse_rule(rule: &str) -> Result<((char, char), char)> {
+    let (input, output) = rule
+        .split_once(" -> ")
+        .ok_or_else(|| Error::parse(DayFourteen::DAY, rule, "expected a rule `AB -> C`"))?;
 
-            let left = input.remove(0);
-            let right = input.remove(0);
-            let output = output.parse().unwrap();
-            rules.insert((left, right), output);
+    let (left, right) = match input.chars().collect::<Vec<char>>()[..] {
+        [left, right] => (left, right),
+        _ => return Err(Error::parse(DayFourteen::DAY, input, "expected a pair").at_column(1)),
+    };
 
-            rules
-        });
+    let output = output.parse().map_err(|_| {
+        Error::parse(DayFourteen::DAY, output, "expected an element")
+            .at_column(column_of(rule, output))
+    })?;
+
+    Ok(((left, right), output))
+}
+
+/// A polymer template and the element inserted between each pair of adjacent elements.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Polymer {
+    template: Vec<char>,
+    rules: HashMap<(char, char), char>,
+}
+
+/// Parses the polymer template, followed by the pair insertion rules.
+pub fn parse_input(input: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Polymer> {
+    let mut lines = input
+        .into_iter()
+        .enumerate()
+        .filter(|(_, line)| !line.as_ref().is_empty());
+
+    let template: Vec<char> = match lines.next() {
+        Some((_, template)) if template.as_ref().chars().count() > 1 => {
+            template.as_ref().chars().collect()
+        }
+        Some((ix, template)) => {
+            let reason = "expected a template of at least two elements";
+            return Err(Error::parse(DayFourteen::DAY, template.as_ref(), reason).at_line(ix + 1));
+        }
+        None => return Err(Error::unsolvable(DayFourteen::DAY, "there is no template")),
+    };
+
+    let rules = lines.try_fold(HashMap::new(), |mut rules, (ix, rule)| {
+        let (input, output) = parse_rule(rule.as_ref()).map_err(|e| e.at_line(ix + 1))?;
+        rules.insert(input, output);
+
+        Ok(rules)
+    })?;
+
+    Ok(Polymer { template, rules })
+}
+
+pub(crate) fn calculate_frequency_range(polymer: &Polymer, steps: usize) -> Result<usize> {
+    calculate_frequency_range_with_observer(polymer, steps, &mut ())
+}
+
+/// Grows the polymer for the given number of `steps`, showing `observer` how often each pair of
+/// adjacent elements appears after each.
+pub fn calculate_frequency_range_with_observer(
+    polymer: &Polymer,
+    steps: usize,
+    observer: &mut impl Observer<HashMap<(char, char), usize>>,
+) -> Result<usize> {
+    let Polymer { template, rules } = polymer;
 
     let mut pairs: HashMap<(char, char), usize> =
         template[..]
@@ -24,48 +76,114 @@ fn calculate_frequency_range(mut input: Vec<String>, steps: usize) -> usize {
                 pairs
             });
 
-    for _ in 0..steps {
-        pairs = pairs
-            .into_iter()
-            .fold(HashMap::new(), |mut new_pairs, ((l, r), frequency)| {
-                let m = rules[&(l, r)];
+    let too_long = || Error::unsolvable(DayFourteen::DAY, "the polymer is too long to count");
 
-                let entry = new_pairs.entry((l, m)).or_default();
-                *entry += frequency;
+    for step in 1..=steps {
+        pairs =
+            pairs
+                .into_iter()
+                .try_fold(HashMap::new(), |mut new_pairs, ((l, r), frequency)| {
+                    let m = *rules.get(&(l, r)).ok_or_else(|| {
+                        let reason = format!("there is no insertion rule for pair `{}{}`", l, r);
+                        Error::unsolvable(DayFourteen::DAY, reason)
+                    })?;
 
-                let entry = new_pairs.entry((m, r)).or_default();
-                *entry += frequency;
+                    for pair in [(l, m), (m, r)] {
+                        let entry: &mut usize = new_pairs.entry(pair).or_default();
+                        *entry = entry.checked_add(frequency).ok_or_else(too_long)?;
+                    }
 
-                new_pairs
-            });
+                    Ok(new_pairs)
+                })?;
+        observer.observe(step, &pairs);
     }
 
+    // Every element but the last is the first of a pair, and the last element never changes.
+    let last = template[template.len() - 1];
     let (max, min) = pairs
         .into_iter()
-        .fold(
-            HashMap::new(),
-            |mut frequencies: HashMap<char, f64>, ((l, r), frequency)| {
+        .try_fold(
+            HashMap::from([(last, 1)]),
+            |mut frequencies: HashMap<char, usize>, ((l, _), frequency)| {
                 let entry = frequencies.entry(l).or_default();
-                *entry += frequency as f64 / 2.;
-
-                let entry = frequencies.entry(r).or_default();
-                *entry += frequency as f64 / 2.;
+                *entry = entry.checked_add(frequency).ok_or_else(too_long)?;
 
-                frequencies
+                Ok(frequencies)
             },
-        )
-        .into_iter()
-        .map(|(_, frequency)| frequency.round() as usize)
+        )?
+        .into_values()
         .fold((0, usize::MAX), |(max, min), frequency| {
             (max.max(frequency), min.min(frequency))
         });
 
-    max - min
+    Ok(max - min)
+}
+
+pub struct DayFourteen;
+
+impl Solution for DayFourteen {
+    const DAY: usize = 14;
+
+    type Input = Polymer;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input.lines())
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        calculate_frequency_range(input, 10)
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        calculate_frequency_range(input, 40)
+    }
+
+    const PARAMETER: Option<&'static str> = Some("steps");
+
+    fn solve_with(input: &Self::Input, steps: usize) -> Result<String> {
+        calculate_frequency_range(input, steps).map(|answer| answer.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    fn use_example_input() -> Vec<String> {
+    use super::Polymer;
+    use crate::{generators, Error};
+    use std::collections::HashMap;
+
+    /// Builds the whole polymer, rather than counting how often each pair of elements appears.
+    fn naive_frequency_range(input: &str, steps: usize) -> usize {
+        let mut lines = input.lines().filter(|line| !line.is_empty());
+        let mut polymer: Vec<char> = lines.next().unwrap().chars().collect();
+        let rules: HashMap<(char, char), char> = lines
+            .map(|rule| {
+                let rule: Vec<char> = rule.chars().collect();
+                ((rule[0], rule[1]), rule[6])
+            })
+            .collect();
+
+        for _ in 0..steps {
+            let mut next = vec![polymer[0]];
+
+            for pair in polymer.windows(2) {
+                next.push(rules[&(pair[0], pair[1])]);
+                next.push(pair[1]);
+            }
+
+            polymer = next;
+        }
+
+        let mut frequencies: HashMap<char, usize> = HashMap::new();
+        for element in polymer {
+            *frequencies.entry(element).or_default() += 1;
+        }
+
+        frequencies.values().max().unwrap() - frequencies.values().min().unwrap()
+    }
+
+    fn use_example_lines() -> Vec<String> {
         String::from(
             r#"NNCB
 
@@ -92,19 +210,19 @@ CN -> C"#,
         .collect()
     }
 
-    fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_fourteen.txt")
-            .lines()
-            .map(String::from)
-            .filter(|l| !l.is_empty())
-            .collect()
+    fn use_example_input() -> Polymer {
+        super::parse_input(use_example_lines()).unwrap()
+    }
+
+    fn use_real_input() -> Polymer {
+        super::parse_input(include_str!("../input/default/day_fourteen.txt").lines()).unwrap()
     }
 
     #[test]
     fn test_calculate_frequency_range_with_example_input_and_ten_steps() {
         let input = use_example_input();
-        let expected = 1588;
-        let actual = super::calculate_frequency_range(input, 10);
+        let expected = Ok(1588);
+        let actual = super::calculate_frequency_range(&input, 10);
 
         assert_eq!(expected, actual);
     }
@@ -112,8 +230,8 @@ CN -> C"#,
     #[test]
     fn test_calculate_frequency_range_with_real_input_and_ten_steps() {
         let input = use_real_input();
-        let expected = 3406;
-        let actual = super::calculate_frequency_range(input, 10);
+        let expected = Ok(3406);
+        let actual = super::calculate_frequency_range(&input, 10);
 
         assert_eq!(expected, actual);
     }
@@ -121,8 +239,8 @@ CN -> C"#,
     #[test]
     fn test_calculate_frequency_range_with_example_input_and_forty_steps() {
         let input = use_example_input();
-        let expected = 2188189693529;
-        let actual = super::calculate_frequency_range(input, 40);
+        let expected = Ok(2188189693529);
+        let actual = super::calculate_frequency_range(&input, 40);
 
         assert_eq!(expected, actual);
     }
@@ -130,8 +248,106 @@ CN -> C"#,
     #[test]
     fn test_calculate_frequency_range_with_real_input_and_forty_steps() {
         let input = use_real_input();
-        let expected = 3941782230241;
-        let actual = super::calculate_frequency_range(input, 40);
+        let expected = Ok(3941782230241);
+        let actual = super::calculate_frequency_range(&input, 40);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_frequency_range_with_observer() {
+        let mut actual = vec![];
+        let expected = vec![
+            (
+                1,
+                HashMap::from([
+                    (('N', 'C'), 1),
+                    (('C', 'N'), 1),
+                    (('N', 'B'), 1),
+                    (('B', 'C'), 1),
+                    (('C', 'H'), 1),
+                    (('H', 'B'), 1),
+                ]),
+            ),
+            (
+                2,
+                HashMap::from([
+                    (('N', 'B'), 2),
+                    (('B', 'C'), 2),
+                    (('C', 'C'), 1),
+                    (('C', 'N'), 1),
+                    (('B', 'B'), 2),
+                    (('C', 'B'), 2),
+                    (('B', 'H'), 1),
+                    (('H', 'C'), 1),
+                ]),
+            ),
+        ];
+        super::calculate_frequency_range_with_observer(
+            &use_example_input(),
+            2,
+            &mut |step, pairs: &HashMap<(char, char), usize>| actual.push((step, pairs.clone())),
+        )
+        .unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_frequency_range_agrees_with_naive_polymer() {
+        for seed in 0..500 {
+            let mut rng = generators::Rng::new(seed);
+            let size = rng.range(2..=8);
+            let input = generators::day_fourteen(&mut rng, size);
+            let steps = rng.range(0..=6);
+
+            let expected = Ok(naive_frequency_range(&input, steps));
+            let polymer = super::parse_input(input.lines()).unwrap();
+            let actual = super::calculate_frequency_range(&polymer, steps);
+
+            assert_eq!(expected, actual, "{} steps\n{}", steps, input);
+        }
+    }
+
+    #[test]
+    fn test_calculate_frequency_range_with_same_first_and_last_element() {
+        let input = super::parse_input(["ABA", "", "AA -> B", "AB -> A", "BA -> B", "BB -> A"]);
+        let expected = Ok(1);
+        let actual = super::calculate_frequency_range(&input.unwrap(), 0);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_invalid_rule() {
+        let mut input = use_example_lines();
+        input[3] = String::from("CBH -> H");
+        let expected = Err(Error::parse(14, "CBH", "expected a pair")
+            .at_line(4)
+            .at_column(1));
+        let actual = super::parse_input(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_frequency_range_with_missing_rule() {
+        let mut input = use_example_lines();
+        input.remove(1);
+        let input = super::parse_input(input).unwrap();
+        let expected = Err(Error::unsolvable(
+            14,
+            "there is no insertion rule for pair `CH`",
+        ));
+        let actual = super::calculate_frequency_range(&input, 10);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_frequency_range_with_polymer_too_long() {
+        let expected = Err(Error::unsolvable(14, "the polymer is too long to count"));
+        let actual = super::calculate_frequency_range(&use_example_input(), 100);
 
         assert_eq!(expected, actual);
     }
@@ -1,119 +1,90 @@
-use crate::position::Position;
+use crate::{
+    grid::{self, Grid},
+    Error, Result, Solution,
+};
 
-fn parse_input(input: Vec<String>, height: usize, width: usize) -> Vec<Vec<u32>> {
-    let mut matrix = vec![vec![0u32; width]; height];
+fn parse_input(input: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Grid<u32>> {
+    let decode = grid::digit(DayNine::DAY, "invalid height");
 
-    for (rix, row) in input.iter().enumerate() {
-        for (cix, col) in row.chars().enumerate() {
-            matrix[rix][cix] = col.to_digit(10).unwrap();
-        }
-    }
+    Grid::parse(DayNine::DAY, input, "heights", decode)
+}
 
-    matrix
+pub(crate) fn sum_risk_levels(height_map: &Grid<u32>) -> u32 {
+    height_map
+        .iter()
+        .filter(|&(position, height)| {
+            height_map
+                .neighbours_orthogonal(position)
+                .all(|next| *height < height_map[next])
+        })
+        .map(|(_, height)| height + 1)
+        .sum()
 }
 
-fn spread(
-    current: Position,
-    candidates: &mut Vec<Position>,
-    basin: &mut Vec<Position>,
-    boundaries: &mut Vec<Position>,
-    height_map: &Vec<Vec<u32>>,
-    height: usize,
-    width: usize,
-) {
-    if let Some(position) = candidates.iter().position(|p| *p == current) {
-        // remove current position from candidates
-        candidates.remove(position);
-    } else {
-        // if candidate has previously been removed, return
-        return;
-    }
+pub(crate) fn multiply_basin_sizes(height_map: &Grid<u32>) -> Result<usize> {
+    // Heights of `9` bound the basins, so they are never part of one.
+    let mut visited = height_map.map(|height| *height == 9);
+    let mut basins = vec![];
 
-    // if current position has a height value of `9`, we have reached the edge of a basin
-    // add current position to boundaries and return
-    if height_map[current.y][current.x] == 9 {
-        boundaries.push(current);
-        return;
-    }
+    for start in height_map.positions() {
+        if visited[start] {
+            continue;
+        }
 
-    // otherwise, must still be in the current basin, add to the basin
-    basin.push(current);
-
-    // if next position to right is still a candidate, move there
-    current
-        .neighbours_orthogonal(height, width)
-        .into_iter()
-        .for_each(|next| {
-            spread(
-                next, candidates, basin, boundaries, height_map, height, width,
-            )
-        });
-}
+        visited[start] = true;
+        let mut stack = vec![start];
+        let mut size = 0;
 
-fn sum_risk_levels(input: Vec<String>) -> u32 {
-    let height = input.len();
-    let width = input.get(0).map(|row| row.len()).unwrap_or_default();
-    let matrix = parse_input(input, height, width);
-
-    let mut low_points = vec![];
-    for (rix, _) in matrix.iter().enumerate() {
-        for (cix, col) in matrix[rix].iter().enumerate() {
-            let top = rix == 0 || (rix > 0 && *col < matrix[rix - 1][cix]);
-            let bottom = rix == height - 1 || (rix < height - 1 && *col < matrix[rix + 1][cix]);
-            let left = cix == 0 || (cix > 0 && *col < matrix[rix][cix - 1]);
-            let right = cix == width - 1 || (cix < width - 1 && *col < matrix[rix][cix + 1]);
-
-            if top && bottom && left && right {
-                low_points.push(*col);
+        while let Some(current) = stack.pop() {
+            size += 1;
+
+            for next in height_map.neighbours_orthogonal(current) {
+                if !visited[next] {
+                    visited[next] = true;
+                    stack.push(next);
+                }
             }
         }
+
+        basins.push(size);
+    }
+
+    if basins.len() < 3 {
+        return Err(Error::unsolvable(DayNine::DAY, "fewer than three basins"));
     }
 
-    low_points.into_iter().map(|lp| lp + 1).sum()
+    basins.sort_unstable();
+
+    Ok(basins[basins.len() - 3..].iter().product())
 }
 
-fn multiply_basin_sizes(input: Vec<String>) -> usize {
-    let height = input.len();
-    let width = input.get(0).map(|row| row.len()).unwrap_or_default();
-    let height_map = parse_input(input, height, width);
+pub struct DayNine;
 
-    let mut basins = vec![];
-    let mut boundaries = vec![];
-
-    let mut candidates = (0..height).into_iter().fold(vec![], |mut locs, y| {
-        let mut row = (0..width).into_iter().map(|x| Position { x, y }).collect();
-        locs.append(&mut row);
-        locs
-    });
-
-    while let Some(start) = candidates.get(0) {
-        let mut basin = vec![];
-
-        spread(
-            *start,
-            &mut candidates,
-            &mut basin,
-            &mut boundaries,
-            &height_map,
-            height,
-            width,
-        );
-
-        if !basin.is_empty() {
-            basins.push(basin);
-        }
+impl Solution for DayNine {
+    const DAY: usize = 9;
+
+    type Input = Grid<u32>;
+    type PartOne = u32;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input.lines())
     }
 
-    basins.sort_by(|a, b| a.len().cmp(&b.len()));
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(sum_risk_levels(input))
+    }
 
-    basins[basins.len() - 3..]
-        .iter()
-        .fold(1, |product, basin| product * basin.len())
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        multiply_basin_sizes(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    fn use_example_input() -> Vec<String> {
+    use crate::{grid::Grid, Error};
+
+    fn use_example_lines() -> Vec<String> {
         String::from(
             r#"2199943210
 3987894921
@@ -126,18 +97,19 @@ mod tests {
         .collect()
     }
 
-    fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_nine.txt")
-            .lines()
-            .map(String::from)
-            .collect()
+    fn use_example_input() -> Grid<u32> {
+        super::parse_input(use_example_lines()).unwrap()
+    }
+
+    fn use_real_input() -> Grid<u32> {
+        super::parse_input(include_str!("../input/default/day_nine.txt").lines()).unwrap()
     }
 
     #[test]
     fn test_sum_risk_levels_with_example_input() {
         let input = use_example_input();
         let expected = 15;
-        let actual = super::sum_risk_levels(input);
+        let actual = super::sum_risk_levels(&input);
 
         assert_eq!(expected, actual);
     }
@@ -146,7 +118,7 @@ mod tests {
     fn test_sum_risk_levels_with_real_input() {
         let input = use_real_input();
         let expected = 580;
-        let actual = super::sum_risk_levels(input);
+        let actual = super::sum_risk_levels(&input);
 
         assert_eq!(expected, actual);
     }
@@ -154,8 +126,8 @@ mod tests {
     #[test]
     fn test_multiply_basin_sizes_with_example_input() {
         let input = use_example_input();
-        let expected = 1134;
-        let actual = super::multiply_basin_sizes(input);
+        let expected = Ok(1134);
+        let actual = super::multiply_basin_sizes(&input);
 
         assert_eq!(expected, actual);
     }
@@ -163,8 +135,30 @@ mod tests {
     #[test]
     fn test_multiply_basin_sizes_with_real_input() {
         let input = use_real_input();
-        let expected = 856716;
-        let actual = super::multiply_basin_sizes(input);
+        let expected = Ok(856716);
+        let actual = super::multiply_basin_sizes(&input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_invalid_height() {
+        let mut input = use_example_lines();
+        input[3] = String::from("876789678?");
+        let expected = Err(Error::parse(9, "?", "invalid height")
+            .at_line(4)
+            .at_column(10));
+        let actual = super::parse_input(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_ragged_rows() {
+        let mut input = use_example_lines();
+        input[1] = String::from("398789492");
+        let expected = Err(Error::parse(9, "398789492", "expected 10 heights").at_line(2));
+        let actual = super::parse_input(input);
 
         assert_eq!(expected, actual);
     }
@@ -1,20 +1,103 @@
+use crate::{process_results, Error, Part, Result, Solution};
+use std::{borrow::Borrow, collections::VecDeque};
+
 /// Returns the frequency with which the sum of a sliding window is an increase on the sum of the
 /// previous sliding window. To return the frequency with which an individual measurement is an
 /// increase on the previous measurement, set `window_size` to `1`.
 ///
 /// For successive sliding windows, A and B, an increase may be detected if the final element of B
 /// is greater than the first element of A, all remaining elements are common to both A and B.
-fn count_increases(measurements: &Vec<usize>, window_size: usize) -> usize {
+///
+/// Only the last `window_size` measurements are held at any time, so `measurements` may be streamed.
+pub(crate) fn count_increases<I>(measurements: I, window_size: usize) -> usize
+where
+    I: IntoIterator,
+    I::Item: Borrow<usize>,
+{
+    let mut window = VecDeque::with_capacity(window_size + 1);
+
     measurements
-        .windows(window_size + 1)
-        .filter(|window| window[window_size] > window[0])
+        .into_iter()
+        .filter(|measurement| {
+            window.push_back(*measurement.borrow());
+
+            if window.len() > window_size {
+                window.pop_front() < window.back().copied()
+            } else {
+                false
+            }
+        })
         .count()
 }
 
+fn parse_measurements<I, S>(lines: I) -> impl Iterator<Item = Result<usize>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    lines.into_iter().enumerate().map(|(ix, line)| {
+        let line = line.as_ref();
+        line.parse()
+            .map_err(|_| Error::parse(DayOne::DAY, line, "invalid measurement").at_line(ix + 1))
+    })
+}
+
+pub struct DayOne;
+
+impl Solution for DayOne {
+    const DAY: usize = 1;
+
+    type Input = Vec<usize>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_measurements(input.lines()).collect()
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(count_increases(input, 1))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(count_increases(input, 3))
+    }
+
+    const PARAMETER: Option<&'static str> = Some("window size");
+
+    fn solve_with(input: &Self::Input, window_size: usize) -> Result<String> {
+        Ok(count_increases(input, window_size).to_string())
+    }
+
+    fn solve_lines(lines: &mut dyn Iterator<Item = String>, part: Part) -> Result<String> {
+        let window_size = match part {
+            Part::One => 1,
+            Part::Two => 3,
+        };
+
+        process_results(parse_measurements(lines), |measurements| {
+            count_increases(measurements, window_size).to_string()
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::{generators::Rng, Error, Solution};
+
+    /// Sums every window before comparing them, rather than comparing only the measurements that
+    /// differ between successive windows.
+    fn naive_count_increases(measurements: &[usize], window_size: usize) -> usize {
+        let sums: Vec<usize> = measurements
+            .windows(window_size)
+            .map(|window| window.iter().sum())
+            .collect();
+
+        sums.windows(2).filter(|pair| pair[0] < pair[1]).count()
+    }
+
     fn read_measurements_from_input_file() -> Vec<usize> {
-        include_str!("../input/day_one.txt")
+        include_str!("../input/default/day_one.txt")
             .lines()
             .map(|line| line.parse::<usize>().expect("Measurement is invalid"))
             .collect()
@@ -55,4 +138,27 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_count_increases_agrees_with_naive_count() {
+        for seed in 0..500 {
+            let mut rng = Rng::new(seed);
+            let measurements: Vec<usize> =
+                (0..rng.range(0..=20)).map(|_| rng.range(0..=10)).collect();
+            let window_size = rng.range(1..=4);
+
+            let expected = naive_count_increases(&measurements, window_size);
+            let actual = super::count_increases(&measurements, window_size);
+
+            assert_eq!(expected, actual, "{:?}, {}", measurements, window_size);
+        }
+    }
+
+    #[test]
+    fn test_parse_with_invalid_measurement() {
+        let expected = Err(Error::parse(1, "2O8", "invalid measurement").at_line(3));
+        let actual = super::DayOne::parse("199\n200\n2O8\n210");
+
+        assert_eq!(expected, actual);
+    }
 }
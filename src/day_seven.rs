@@ -1,67 +1,139 @@
-use std::usize;
+use crate::{error::column_of, Error, Result, Solution};
 
-fn parse_input(input: String) -> Vec<isize> {
+fn parse_input(input: &str) -> Result<Vec<isize>> {
     input
         .split(',')
-        .filter_map(|position| position.parse().ok())
+        .map(|position| {
+            position.parse().map_err(|_| {
+                Error::parse(DaySeven::DAY, position, "invalid position")
+                    .at_column(column_of(input, position))
+            })
+        })
         .collect()
 }
 
-fn calculate_increasing_fuel_usage(distance: isize) -> usize {
-    let distance = distance as f64;
-    ((distance / 2.) * (distance + 1.)).ceil() as usize
+fn too_much_fuel() -> Error {
+    Error::unsolvable(DaySeven::DAY, "fuel usage is too large")
 }
 
-fn calculate_minimum_constant_fuel_usage(input: String) -> usize {
-    let mut positions = parse_input(input);
+/// Returns the triangular number of `distance`, or `None` if it overflows.
+fn calculate_increasing_fuel_usage(distance: usize) -> Option<usize> {
+    let next = distance.checked_add(1)?;
+
+    if distance.is_multiple_of(2) {
+        (distance / 2).checked_mul(next)
+    } else {
+        distance.checked_mul(next / 2)
+    }
+}
+
+pub(crate) fn calculate_minimum_constant_fuel_usage(positions: &[isize]) -> Result<usize> {
+    let mut positions = positions.to_vec();
     positions.sort();
 
     let len = positions.len();
-    let median_ix = if len % 2 == 0 { len / 2 - 1 } else { len / 2 };
+    let median_ix = if len.is_multiple_of(2) {
+        len / 2 - 1
+    } else {
+        len / 2
+    };
     let median = positions[median_ix];
 
     positions
         .iter()
-        .map(|position| (position - median).abs() as usize)
-        .sum()
+        .try_fold(0usize, |total, position| {
+            total.checked_add(position.abs_diff(median))
+        })
+        .ok_or_else(too_much_fuel)
 }
 
-fn calculate_minimum_increasing_fuel_usage(input: String) -> usize {
-    let positions = parse_input(input);
+pub(crate) fn calculate_minimum_increasing_fuel_usage(positions: &[isize]) -> Result<usize> {
     let sum = positions
         .iter()
         .fold(0., |sum, position| sum + *position as f64);
 
     let mean = sum / positions.len() as f64;
-    let lo_mean = mean.floor() as isize;
-    let hi_mean = mean.ceil() as isize;
+    let fuel_usage = |target: isize| {
+        positions.iter().try_fold(0usize, |total, position| {
+            calculate_increasing_fuel_usage(position.abs_diff(target))
+                .and_then(|fuel| total.checked_add(fuel))
+        })
+    };
+
+    [mean.floor() as isize, mean.ceil() as isize]
+        .into_iter()
+        .filter_map(fuel_usage)
+        .min()
+        .ok_or_else(too_much_fuel)
+}
 
-    let (lo_sum, hi_sum) = positions
-        .iter()
-        .fold((0, 0), |(lo_total, hi_total), position| {
-            let lo = calculate_increasing_fuel_usage((position - lo_mean).abs());
-            let hi = calculate_increasing_fuel_usage((position - hi_mean).abs());
-            (lo_total + lo, hi_total + hi)
-        });
+pub struct DaySeven;
+
+impl Solution for DaySeven {
+    const DAY: usize = 7;
 
-    lo_sum.min(hi_sum)
+    type Input = Vec<isize>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input.trim())
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        calculate_minimum_constant_fuel_usage(input)
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        calculate_minimum_increasing_fuel_usage(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    fn use_example_input() -> String {
-        String::from("16,1,2,0,4,2,7,1,2,14")
+    use crate::{generators::Rng, Error};
+
+    /// Tries aligning on every position between the outermost crabs, rather than only on the median
+    /// or either side of the mean.
+    fn naive_minimum_fuel_usage(positions: &[usize], fuel_usage: fn(usize) -> usize) -> usize {
+        let (min, max) = (positions.iter().min(), positions.iter().max());
+
+        (*min.unwrap()..=*max.unwrap())
+            .map(|target| {
+                positions
+                    .iter()
+                    .map(|position| fuel_usage(position.abs_diff(target)))
+                    .sum()
+            })
+            .min()
+            .unwrap()
+    }
+
+    fn use_random_positions(seed: u64) -> (Vec<usize>, String) {
+        let mut rng = Rng::new(seed);
+        let positions: Vec<usize> = (0..rng.range(1..=10)).map(|_| rng.range(0..=20)).collect();
+        let input = positions
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        (positions, input)
+    }
+
+    fn use_example_input() -> Vec<isize> {
+        super::parse_input("16,1,2,0,4,2,7,1,2,14").unwrap()
     }
 
-    fn use_real_input() -> String {
-        include_str!("../input/day_seven.txt").trim().to_owned()
+    fn use_real_input() -> Vec<isize> {
+        super::parse_input(include_str!("../input/default/day_seven.txt").trim()).unwrap()
     }
 
     #[test]
     fn test_calculate_minimum_constant_fuel_usage_with_example_input() {
         let input = use_example_input();
-        let expected = 37;
-        let actual = super::calculate_minimum_constant_fuel_usage(input);
+        let expected = Ok(37);
+        let actual = super::calculate_minimum_constant_fuel_usage(&input);
 
         assert_eq!(expected, actual);
     }
@@ -69,8 +141,8 @@ mod tests {
     #[test]
     fn test_calculate_minimum_constant_fuel_usage_with_real_input() {
         let input = use_real_input();
-        let expected = 364898;
-        let actual = super::calculate_minimum_constant_fuel_usage(input);
+        let expected = Ok(364898);
+        let actual = super::calculate_minimum_constant_fuel_usage(&input);
 
         assert_eq!(expected, actual);
     }
@@ -78,8 +150,8 @@ mod tests {
     #[test]
     fn test_calculate_minimum_increasing_fuel_usage_with_example_input() {
         let input = use_example_input();
-        let expected = 168;
-        let actual = super::calculate_minimum_increasing_fuel_usage(input);
+        let expected = Ok(168);
+        let actual = super::calculate_minimum_increasing_fuel_usage(&input);
 
         assert_eq!(expected, actual);
     }
@@ -87,8 +159,51 @@ mod tests {
     #[test]
     fn test_calculate_minimum_increasing_fuel_usage_with_real_input() {
         let input = use_real_input();
-        let expected = 104149091;
-        let actual = super::calculate_minimum_increasing_fuel_usage(input);
+        let expected = Ok(104149091);
+        let actual = super::calculate_minimum_increasing_fuel_usage(&input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_minimum_constant_fuel_usage_agrees_with_naive_search() {
+        for seed in 0..500 {
+            let (positions, input) = use_random_positions(seed);
+            let expected = Ok(naive_minimum_fuel_usage(&positions, |distance| distance));
+            let actual =
+                super::calculate_minimum_constant_fuel_usage(&super::parse_input(&input).unwrap());
+
+            assert_eq!(expected, actual, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_calculate_minimum_increasing_fuel_usage_agrees_with_naive_search() {
+        for seed in 0..500 {
+            let (positions, input) = use_random_positions(seed);
+            let expected = Ok(naive_minimum_fuel_usage(&positions, |distance| {
+                (1..=distance).sum()
+            }));
+            let actual = super::calculate_minimum_increasing_fuel_usage(
+                &super::parse_input(&input).unwrap(),
+            );
+
+            assert_eq!(expected, actual, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_input_with_invalid_position() {
+        let expected = Err(Error::parse(7, "", "invalid position").at_column(6));
+        let actual = super::parse_input("16,1,,0");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_minimum_increasing_fuel_usage_with_too_much_fuel() {
+        let expected = Err(Error::unsolvable(7, "fuel usage is too large"));
+        let actual = super::calculate_minimum_increasing_fuel_usage(&[0, 9223372036854775807]);
 
         assert_eq!(expected, actual);
     }
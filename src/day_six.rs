@@ -1,35 +1,114 @@
-fn model_population(seed: String, duration: usize) -> usize {
-    let mut population: Vec<usize> = seed.split(',').filter_map(|timer| timer.parse().ok()).fold(
-        vec![0; 9],
-        |mut summary, fish: usize| {
-            summary[fish] += 1;
-            summary
-        },
-    );
-
-    (0..duration).for_each(|_day| {
+use crate::{error::column_of, observer::Observer, Error, Result, Solution};
+
+/// How many fish have each timer, from zero to eight.
+type Population = [usize; 9];
+
+fn parse_population(seed: &str) -> Result<Population> {
+    seed.split(',').try_fold([0; 9], |mut population, timer| {
+        match timer.parse::<usize>() {
+            Ok(fish) if fish < population.len() => {
+                population[fish] += 1;
+                Ok(population)
+            }
+            _ => {
+                Err(Error::parse(DaySix::DAY, timer, "invalid timer")
+                    .at_column(column_of(seed, timer)))
+            }
+        }
+    })
+}
+
+pub(crate) fn model_population(population: &Population, duration: usize) -> Result<usize> {
+    model_population_with_observer(population, duration, &mut ())
+}
+
+/// Models the population for `duration` days, showing `observer` how many fish have each timer,
+/// from zero to eight, at the end of every day.
+pub fn model_population_with_observer(
+    population: &Population,
+    duration: usize,
+    observer: &mut impl Observer<[usize]>,
+) -> Result<usize> {
+    let mut population = *population;
+    let too_many_fish = || Error::unsolvable(DaySix::DAY, "there are too many fish to count");
+
+    for day in 1..=duration {
         population.rotate_left(1);
-        population[6] += population[8];
-    });
+        population[6] = population[6]
+            .checked_add(population[8])
+            .ok_or_else(too_many_fish)?;
+        observer.observe(day, &population);
+    }
+
+    population
+        .iter()
+        .try_fold(0usize, |total, fish| total.checked_add(*fish))
+        .ok_or_else(too_many_fish)
+}
+
+pub struct DaySix;
+
+impl Solution for DaySix {
+    const DAY: usize = 6;
 
-    population.iter().sum()
+    type Input = Population;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_population(input.trim())
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        model_population(input, 80)
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        model_population(input, 256)
+    }
+
+    const PARAMETER: Option<&'static str> = Some("duration");
+
+    fn solve_with(input: &Self::Input, duration: usize) -> Result<String> {
+        model_population(input, duration).map(|answer| answer.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    fn use_example_input() -> String {
-        String::from("3,4,3,1,2")
+    use super::Population;
+    use crate::{generators::Rng, Error};
+
+    /// Models every fish individually, rather than how many fish share each timer.
+    fn naive_model_population(timers: &[usize], duration: usize) -> usize {
+        let mut fish = timers.to_vec();
+
+        for _day in 0..duration {
+            let spawned = fish.iter().filter(|timer| **timer == 0).count();
+
+            for timer in fish.iter_mut() {
+                *timer = if *timer == 0 { 6 } else { *timer - 1 };
+            }
+
+            fish.extend(vec![8; spawned]);
+        }
+
+        fish.len()
+    }
+
+    fn use_example_input() -> Population {
+        super::parse_population("3,4,3,1,2").unwrap()
     }
 
-    fn use_real_input() -> String {
-        include_str!("../input/day_six.txt").trim().to_owned()
+    fn use_real_input() -> Population {
+        super::parse_population(include_str!("../input/default/day_six.txt").trim()).unwrap()
     }
 
     #[test]
     fn test_model_population_with_example_input() {
         let input = use_example_input();
-        let expected = 5934;
-        let actual = super::model_population(input, 80);
+        let expected = Ok(5934);
+        let actual = super::model_population(&input, 80);
 
         assert_eq!(expected, actual);
     }
@@ -37,8 +116,8 @@ mod tests {
     #[test]
     fn test_model_population_with_real_input() {
         let input = use_real_input();
-        let expected = 390011;
-        let actual = super::model_population(input, 80);
+        let expected = Ok(390011);
+        let actual = super::model_population(&input, 80);
 
         assert_eq!(expected, actual);
     }
@@ -46,8 +125,8 @@ mod tests {
     #[test]
     fn test_model_population_with_example_input_and_longer_duration() {
         let input = use_example_input();
-        let expected = 26984457539;
-        let actual = super::model_population(input, 256);
+        let expected = Ok(26984457539);
+        let actual = super::model_population(&input, 256);
 
         assert_eq!(expected, actual);
     }
@@ -55,8 +134,62 @@ mod tests {
     #[test]
     fn test_model_population_with_real_input_and_longer_lifespan() {
         let input = use_real_input();
-        let expected = 1746710169834;
-        let actual = super::model_population(input, 256);
+        let expected = Ok(1746710169834);
+        let actual = super::model_population(&input, 256);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_model_population_agrees_with_naive_model() {
+        for seed in 0..500 {
+            let mut rng = Rng::new(seed);
+            let timers: Vec<usize> = (0..rng.range(1..=10)).map(|_| rng.range(0..=8)).collect();
+            let duration = rng.range(0..=50);
+            let seed = timers
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+
+            let expected = Ok(naive_model_population(&timers, duration));
+            let actual =
+                super::model_population(&super::parse_population(&seed).unwrap(), duration);
+
+            assert_eq!(expected, actual, "{}, {}", seed, duration);
+        }
+    }
+
+    #[test]
+    fn test_model_population_with_observer() {
+        let mut actual = vec![];
+        let expected = vec![
+            (1, vec![1, 1, 2, 1, 0, 0, 0, 0, 0]),
+            (2, vec![1, 2, 1, 0, 0, 0, 1, 0, 1]),
+            (3, vec![2, 1, 0, 0, 0, 1, 1, 1, 1]),
+        ];
+        super::model_population_with_observer(
+            &use_example_input(),
+            3,
+            &mut |day, population: &[usize]| actual.push((day, population.to_vec())),
+        )
+        .unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_population_with_invalid_timer() {
+        let expected = Err(Error::parse(6, "9", "invalid timer").at_column(5));
+        let actual = super::parse_population("3,4,9,1,2");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_model_population_with_too_many_fish() {
+        let expected = Err(Error::unsolvable(6, "there are too many fish to count"));
+        let actual = super::model_population(&use_example_input(), 1000);
 
         assert_eq!(expected, actual);
     }
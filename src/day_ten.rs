@@ -1,95 +1,174 @@
-fn calculate_corrupted_score(input: Vec<String>) -> usize {
-    input.into_iter().fold(0, |sum, line| {
-        let mut stack = vec![];
-        for symbol in line.chars() {
-            match symbol {
-                '(' | '[' | '{' | '<' => stack.push(symbol),
-                ')' => {
-                    if stack.pop() != Some('(') {
-                        return sum + 3;
-                    }
-                }
-                ']' => {
-                    if stack.pop() != Some('[') {
-                        return sum + 57;
-                    }
-                }
-                '}' => {
-                    if stack.pop() != Some('{') {
-                        return sum + 1197;
-                    }
-                }
-                '>' => {
-                    if stack.pop() != Some('<') {
-                        return sum + 25137;
-                    }
-                }
-                _ => {}
-            }
+use crate::{process_results, Error, Part, Result, Solution};
+use std::borrow::Borrow;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Bracket {
+    Round,
+    Square,
+    Curly,
+    Angle,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Symbol {
+    Open(Bracket),
+    Close(Bracket),
+}
+
+impl TryFrom<char> for Symbol {
+    type Error = Error;
+
+    fn try_from(symbol: char) -> Result<Self> {
+        match symbol {
+            '(' => Ok(Symbol::Open(Bracket::Round)),
+            '[' => Ok(Symbol::Open(Bracket::Square)),
+            '{' => Ok(Symbol::Open(Bracket::Curly)),
+            '<' => Ok(Symbol::Open(Bracket::Angle)),
+            ')' => Ok(Symbol::Close(Bracket::Round)),
+            ']' => Ok(Symbol::Close(Bracket::Square)),
+            '}' => Ok(Symbol::Close(Bracket::Curly)),
+            '>' => Ok(Symbol::Close(Bracket::Angle)),
+            _ => Err(Error::parse(DayTen::DAY, symbol, "invalid symbol")),
         }
+    }
+}
 
-        sum
-    })
+fn parse_line(line: &str) -> Result<Vec<Symbol>> {
+    line.chars()
+        .enumerate()
+        .map(|(cix, symbol)| Symbol::try_from(symbol).map_err(|e| e.at_column(cix + 1)))
+        .collect()
 }
 
-fn calculate_incomplete_score(input: Vec<String>) -> usize {
-    let mut scores: Vec<usize> = input
+fn parse_lines<I, S>(lines: I) -> impl Iterator<Item = Result<Vec<Symbol>>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    lines
         .into_iter()
-        .filter_map(|line| {
-            let mut stack = vec![];
-            for symbol in line.chars() {
-                match symbol {
-                    '(' | '[' | '{' | '<' => stack.push(symbol),
-                    ')' => {
-                        if stack.pop() != Some('(') {
-                            return None;
-                        }
-                    }
-                    ']' => {
-                        if stack.pop() != Some('[') {
-                            return None;
-                        }
-                    }
-                    '}' => {
-                        if stack.pop() != Some('{') {
-                            return None;
-                        }
-                    }
-                    '>' => {
-                        if stack.pop() != Some('<') {
-                            return None;
-                        }
-                    }
-                    _ => {}
-                };
+        .enumerate()
+        .map(|(ix, line)| parse_line(line.as_ref()).map_err(|e| e.at_line(ix + 1)))
+}
+
+enum Status {
+    /// The first closing bracket that does not match the open chunk.
+    Corrupted(Bracket),
+    /// The brackets still open at the end of the line, innermost last.
+    Incomplete(Vec<Bracket>),
+}
+
+fn check_line(line: &[Symbol]) -> Status {
+    let mut stack = vec![];
+
+    for &symbol in line {
+        match symbol {
+            Symbol::Open(bracket) => stack.push(bracket),
+            Symbol::Close(bracket) => {
+                if stack.pop() != Some(bracket) {
+                    return Status::Corrupted(bracket);
+                }
             }
+        }
+    }
+
+    Status::Incomplete(stack)
+}
 
-            stack
-                .into_iter()
-                .rev()
-                .fold(0, |total, symbol| match symbol {
-                    '(' => total * 5 + 1,
-                    '[' => total * 5 + 2,
-                    '{' => total * 5 + 3,
-                    '<' => total * 5 + 4,
-                    _ => total,
-                })
-                .into()
+/// Only a single line is held at any time, so `lines` may be streamed.
+pub(crate) fn calculate_corrupted_score(
+    lines: impl IntoIterator<Item = impl Borrow<Vec<Symbol>>>,
+) -> usize {
+    lines
+        .into_iter()
+        .map(|line| match check_line(line.borrow()) {
+            Status::Corrupted(Bracket::Round) => 3,
+            Status::Corrupted(Bracket::Square) => 57,
+            Status::Corrupted(Bracket::Curly) => 1197,
+            Status::Corrupted(Bracket::Angle) => 25137,
+            Status::Incomplete(_) => 0,
         })
-        .collect();
+        .sum()
+}
+
+/// Only the score of each incomplete line is kept, so `lines` may be streamed.
+pub(crate) fn calculate_incomplete_score(
+    lines: impl IntoIterator<Item = impl Borrow<Vec<Symbol>>>,
+) -> Result<usize> {
+    let mut scores = vec![];
+
+    for line in lines {
+        let stack = match check_line(line.borrow()) {
+            Status::Corrupted(_) => continue,
+            Status::Incomplete(stack) => stack,
+        };
+
+        let score = stack.into_iter().rev().try_fold(0usize, |total, bracket| {
+            let points = match bracket {
+                Bracket::Round => 1,
+                Bracket::Square => 2,
+                Bracket::Curly => 3,
+                Bracket::Angle => 4,
+            };
+            total.checked_mul(5)?.checked_add(points)
+        });
+
+        scores.push(
+            score.ok_or_else(|| Error::unsolvable(DayTen::DAY, "completion score is too large"))?,
+        );
+    }
+
+    if scores.is_empty() {
+        return Err(Error::unsolvable(DayTen::DAY, "no incomplete lines"));
+    }
 
     scores.sort();
 
-    if scores.len() % 2 == 0 {
-        scores[scores.len() / 2]
+    if scores.len().is_multiple_of(2) {
+        Ok(scores[scores.len() / 2])
     } else {
-        scores[(scores.len() - 1) / 2]
+        Ok(scores[(scores.len() - 1) / 2])
+    }
+}
+
+pub struct DayTen;
+
+impl Solution for DayTen {
+    const DAY: usize = 10;
+
+    type Input = Vec<Vec<Symbol>>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input.lines()).collect()
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(calculate_corrupted_score(input))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        calculate_incomplete_score(input)
+    }
+
+    fn solve_lines(lines: &mut dyn Iterator<Item = String>, part: Part) -> Result<String> {
+        process_results(parse_lines(lines), |lines| match part {
+            Part::One => Ok(calculate_corrupted_score(lines)),
+            Part::Two => calculate_incomplete_score(lines),
+        })?
+        .map(|answer| answer.to_string())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    fn use_example_input() -> Vec<String> {
+    use crate::day_ten::{DayTen, Symbol};
+    use crate::{Error, Result, Solution};
+
+    fn use_example_lines() -> Vec<String> {
         String::from(
             r#"[({(<(())[]>[[{[]{<()<>>
 [(()[<>])]({[<{<<[]>>(
@@ -107,11 +186,12 @@ mod tests {
         .collect()
     }
 
-    fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_ten.txt")
-            .lines()
-            .map(String::from)
-            .collect()
+    fn use_example_input() -> Vec<Vec<Symbol>> {
+        DayTen::parse(&use_example_lines().join("\n")).unwrap()
+    }
+
+    fn use_real_input() -> Vec<Vec<Symbol>> {
+        DayTen::parse(include_str!("../input/default/day_ten.txt")).unwrap()
     }
 
     #[test]
@@ -135,7 +215,7 @@ mod tests {
     #[test]
     fn test_calculate_incomplete_score_with_example_input() {
         let input = use_example_input();
-        let expected = 288957;
+        let expected = Ok(288957);
         let actual = super::calculate_incomplete_score(input);
 
         assert_eq!(expected, actual);
@@ -144,9 +224,39 @@ mod tests {
     #[test]
     fn test_calculate_incomplete_score_with_real_input() {
         let input = use_real_input();
-        let expected = 4329504793;
+        let expected = Ok(4329504793);
         let actual = super::calculate_incomplete_score(input);
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_parse_with_invalid_symbol() {
+        let mut input = use_example_lines();
+        input[2] = String::from("{([(<{}[<>[]|}>{[]{[(<()>");
+        let expected: Result<Vec<Vec<Symbol>>> = Err(Error::parse(10, '|', "invalid symbol")
+            .at_line(3)
+            .at_column(13));
+        let actual = DayTen::parse(&input.join("\n"));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_incomplete_score_without_incomplete_lines() {
+        let input = DayTen::parse("[({(<(())[]>[[{[]{<()<>>]").unwrap();
+        let expected = Err(Error::unsolvable(10, "no incomplete lines"));
+        let actual = super::calculate_incomplete_score(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_incomplete_score_with_score_too_large() {
+        let input = DayTen::parse(&"<".repeat(30)).unwrap();
+        let expected = Err(Error::unsolvable(10, "completion score is too large"));
+        let actual = super::calculate_incomplete_score(&input);
+
+        assert_eq!(expected, actual);
+    }
 }
@@ -1,75 +1,115 @@
-use crate::position::Position;
-use std::str::FromStr;
+use crate::{
+    error::column_of, grid::Grid, observer::Observer, position::Position, Error, Result, Solution,
+};
+use std::{fmt, str::FromStr};
+
+/// The largest coordinate accepted for a dot or fold, so that the paper always fits in memory.
+const MAX_COORDINATE: usize = 4999;
 
 type Dot = Position;
 
-type DotMap = Vec<Vec<Option<Dot>>>;
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(try_from = "FoldLine")
+)]
+pub enum Fold {
+    X(usize),
+    Y(usize),
+}
 
-#[derive(Clone, Copy, Debug)]
-enum Fold {
+/// A deserialised [`Fold`], before its line is checked.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+enum FoldLine {
     X(usize),
     Y(usize),
 }
 
-impl FromStr for Fold {
-    type Err = ();
+#[cfg(feature = "serde")]
+impl TryFrom<FoldLine> for Fold {
+    type Error = String;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (axis, value) = s.split_once('=').unwrap_or_default();
-        let value = value.parse().unwrap_or_default();
+    /// Accepts the fold only if, as in [`Fold::from_str`], its line is past the first row or
+    /// column and no larger than [`MAX_COORDINATE`].
+    fn try_from(fold: FoldLine) -> std::result::Result<Self, Self::Error> {
+        let (FoldLine::X(line) | FoldLine::Y(line)) = fold;
 
-        match axis {
-            "x" => Ok(Self::X(value)),
-            "y" => Ok(Self::Y(value)),
-            _ => Err(()),
+        if line > MAX_COORDINATE {
+            return Err(format!("fold line {} is too large", line));
         }
+
+        if line == 0 {
+            return Err(String::from("invalid fold line 0"));
+        }
+
+        Ok(match fold {
+            FoldLine::X(x) => Self::X(x),
+            FoldLine::Y(y) => Self::Y(y),
+        })
     }
 }
 
-fn fold_left(map: &mut DotMap, height: usize, width: usize) {
-    let start = width + 1;
-    let end = (start + width).min(map.get(0).map(|row| row.len()).unwrap_or_default());
+impl FromStr for Fold {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (axis, value) = s
+            .split_once('=')
+            .ok_or_else(|| Error::parse(DayThirteen::DAY, s, "expected a fold `axis=value`"))?;
+
+        let value = match value.parse() {
+            Ok(line) if line > MAX_COORDINATE => {
+                return Err(
+                    Error::parse(DayThirteen::DAY, value, "fold line is too large")
+                        .at_column(column_of(s, value)),
+                )
+            }
+            Ok(value) if value > 0 => value,
+            _ => {
+                return Err(Error::parse(DayThirteen::DAY, value, "invalid fold line")
+                    .at_column(column_of(s, value)))
+            }
+        };
 
-    (0..height).for_each(|y| {
-        (start..end).for_each(|x| {
-            let target_col = get_folded_coordinate(x, width);
-            map[y][target_col] = map[y][target_col].or(map[y][x]);
-        });
-        map[y].truncate(width);
-    });
+        match axis {
+            "x" => Ok(Self::X(value)),
+            "y" => Ok(Self::Y(value)),
+            _ => Err(Error::parse(DayThirteen::DAY, axis, "unknown axis").at_column(1)),
+        }
+    }
 }
 
-fn fold_up(map: &mut DotMap, width: usize, height: usize) {
-    let start = height + 1;
-    let end = (start + height).min(map.len());
+/// Folds the right of the paper over onto the left along the vertical line at `line`.
+fn fold_left(map: &mut Grid<bool>, line: usize) {
+    let end = (2 * line + 1).min(map.width());
 
-    // 'fold' rows up
-    (start..end).for_each(|y| {
-        (0..width).for_each(|x| {
-            let target_row = get_folded_coordinate(y, height);
-            map[target_row][x] = map[target_row][x].or(map[y][x]);
-        });
-    });
+    for y in 0..map.height() {
+        for x in line + 1..end {
+            let target = Position { x: 2 * line - x, y };
+            map[target] |= map[Position { x, y }];
+        }
+    }
 
-    map.truncate(height);
+    map.truncate(map.height(), line);
 }
 
-fn get_current_dimensions(map: &DotMap) -> (usize, usize) {
-    (
-        map.len(),
-        map.get(0).map(|row| row.len()).unwrap_or_default(),
-    )
-}
+/// Folds the bottom of the paper up onto the top along the horizontal line at `line`.
+fn fold_up(map: &mut Grid<bool>, line: usize) {
+    let end = (2 * line + 1).min(map.height());
 
-fn get_folded_coordinate(coordinate: usize, limit: usize) -> usize {
-    if coordinate % limit == 0 {
-        0
-    } else {
-        limit - coordinate % limit
+    for y in line + 1..end {
+        for x in 0..map.width() {
+            let target = Position { x, y: 2 * line - y };
+            map[target] |= map[Position { x, y }];
+        }
     }
+
+    map.truncate(line, map.width());
 }
 
-fn get_initial_dimensions(dots: &Vec<Dot>) -> (usize, usize) {
+fn get_initial_dimensions(dots: &[Dot]) -> (usize, usize) {
     let (max_y, max_x) = dots.iter().fold((0, 0), |(height, width), dot| {
         (dot.y.max(height), dot.x.max(width))
     });
@@ -77,88 +117,234 @@ fn get_initial_dimensions(dots: &Vec<Dot>) -> (usize, usize) {
     (max_y + 1, max_x + 1)
 }
 
-fn parse_input(input: Vec<String>) -> (Vec<Dot>, Vec<Fold>) {
+fn parse_dot(line: &str) -> Result<Dot> {
+    let (x, y) = line
+        .split_once(',')
+        .ok_or_else(|| Error::parse(DayThirteen::DAY, line, "expected a dot `x,y`"))?;
+
+    let parse_coordinate = |coordinate: &str| {
+        match coordinate.parse() {
+            Ok(coordinate) if coordinate <= MAX_COORDINATE => Ok(coordinate),
+            Ok(_) => Err(Error::parse(
+                DayThirteen::DAY,
+                coordinate,
+                "coordinate is too large",
+            )),
+            Err(_) => Err(Error::parse(
+                DayThirteen::DAY,
+                coordinate,
+                "invalid coordinate",
+            )),
+        }
+        .map_err(|e| e.at_column(column_of(line, coordinate)))
+    };
+
+    Ok(Dot {
+        x: parse_coordinate(x)?,
+        y: parse_coordinate(y)?,
+    })
+}
+
+/// Parses the dots on the paper and the folds to make in it, in order.
+pub fn parse_input(
+    input: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Result<(Vec<Dot>, Vec<Fold>)> {
     input
         .into_iter()
-        .fold((vec![], vec![]), |(mut dots, mut folds), line| {
-            if line.starts_with("fold") {
-                let (_, fold) = line.rsplit_once(' ').unwrap_or_default();
-                let fold = fold.parse().unwrap();
+        .enumerate()
+        .filter(|(_, line)| !line.as_ref().is_empty())
+        .try_fold((vec![], vec![]), |(mut dots, mut folds), (ix, line)| {
+            let line = line.as_ref();
+
+            if let Some(fold) = line.strip_prefix("fold along ") {
+                let fold = fold
+                    .parse()
+                    .map_err(|e: Error| e.at_column(column_of(line, fold)).at_line(ix + 1))?;
                 folds.push(fold);
             } else {
-                let (x, y) = line.split_once(',').unwrap_or_default();
-                let x = x.parse().unwrap_or_default();
-                let y = y.parse().unwrap_or_default();
-                dots.push(Dot { x, y });
+                dots.push(parse_dot(line).map_err(|e| e.at_line(ix + 1))?);
             }
 
-            (dots, folds)
+            Ok((dots, folds))
         })
 }
 
-fn fold_paper(input: Vec<String>, num_folds: Option<usize>) -> DotMap {
-    let (dots, folds) = parse_input(input);
-    let (height, width) = get_initial_dimensions(&dots);
+/// A sheet of transparent paper as it is between folds, displayed one line per row with `#` for a
+/// dot and `.` for empty paper.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Paper {
+    map: Grid<bool>,
+}
+
+impl Paper {
+    /// Returns the height and width of the paper.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.map.height(), self.map.width())
+    }
+
+    /// Returns how many dots can be seen, counting overlapping dots once.
+    pub fn visible_dots(&self) -> usize {
+        self.map.cells().filter(|&&dot| dot).count()
+    }
+}
+
+impl fmt::Display for Paper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let output: Vec<String> = self
+            .map
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|&dot| if dot { "#" } else { "." })
+                    .collect::<String>()
+            })
+            .collect();
+
+        write!(f, "{}", output.join("\n"))
+    }
+}
+
+fn fold_paper(dots: &[Dot], folds: &[Fold], num_folds: Option<usize>) -> Paper {
+    fold_paper_with_observer(dots, folds, num_folds, &mut ())
+}
 
-    let mut map = vec![vec![None; width]; height];
-    dots.into_iter().for_each(|dot| {
-        map[dot.y][dot.x] = Some(dot);
+/// Folds the paper using the first `num_folds` instructions, or all of them if `None` or there are
+/// fewer, showing `observer` the paper after each fold.
+pub fn fold_paper_with_observer(
+    dots: &[Dot],
+    folds: &[Fold],
+    num_folds: Option<usize>,
+    observer: &mut impl Observer<Paper>,
+) -> Paper {
+    let (height, width) = get_initial_dimensions(dots);
+
+    let mut paper = Paper {
+        map: Grid::new(height, width, false),
+    };
+    dots.iter().for_each(|&dot| {
+        paper.map[dot] = true;
     });
 
     let max_fold = num_folds.unwrap_or(folds.len());
-    (0..max_fold).for_each(|ix| {
-        let (curr_h, curr_w) = get_current_dimensions(&map);
+    folds
+        .iter()
+        .take(max_fold)
+        .enumerate()
+        .for_each(|(ix, fold)| {
+            match fold {
+                Fold::X(x) => fold_left(&mut paper.map, *x),
+                Fold::Y(y) => fold_up(&mut paper.map, *y),
+            }
 
-        if let Some(Fold::X(x)) = folds.get(ix) {
-            fold_left(&mut map, curr_h, *x);
-        }
+            observer.observe(ix + 1, &paper);
+        });
 
-        if let Some(Fold::Y(y)) = folds.get(ix) {
-            fold_up(&mut map, curr_w, *y);
-        }
-    });
+    paper
+}
 
-    map
+pub(crate) fn count_visible_dots(dots: &[Dot], folds: &[Fold], num_folds: usize) -> usize {
+    fold_paper(dots, folds, Some(num_folds)).visible_dots()
 }
 
-fn count_visible_dots(input: Vec<String>, num_folds: usize) -> usize {
-    let map = fold_paper(input, Some(num_folds));
+/// Folds the paper using every instruction and draws the resulting dots, one line per row, with
+/// `#` for a dot and `.` for empty paper.
+pub(crate) fn render_map(dots: &[Dot], folds: &[Fold]) -> String {
+    fold_paper(dots, folds, None).to_string()
+}
 
-    let output: Vec<String> = map
-        .iter()
-        .map(|row| {
-            row.iter()
-                .map(|d| if d.is_some() { "#" } else { "." })
-                .collect::<String>()
+/// The capital letters the folded paper is known to spell out, each drawn four dots wide and six
+/// dots tall in the same style as [`render_map`].
+const LETTERS: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads the letters drawn by a rendered map, which must be six rows tall with each letter taking
+/// four columns followed by a blank one. Returns `None` if any part of the map is not a letter.
+fn read_letters(map: &str) -> Option<String> {
+    let rows: Vec<&str> = map.lines().collect();
+    let width = rows.first()?.len();
+
+    if rows.len() != 6 || rows.iter().any(|row| row.len() != width) {
+        return None;
+    }
+
+    (0..width)
+        .step_by(5)
+        .map(|start| {
+            let glyph: Vec<&str> = rows
+                .iter()
+                .map(|row| row.get(start..start + 4))
+                .collect::<Option<_>>()?;
+            let spacer = rows
+                .iter()
+                .all(|row| row.get(start + 4..start + 5).unwrap_or(".") == ".");
+
+            LETTERS
+                .iter()
+                .find(|(_, letter)| spacer && glyph == letter)
+                .map(|(c, _)| *c)
         })
-        .collect();
+        .collect()
+}
 
-    println!("{}", output.join("\n"));
+/// Folds the paper using every instruction and returns the letters it spells out, or the rendered
+/// map itself if the dots do not form letters.
+pub(crate) fn read_code(dots: &[Dot], folds: &[Fold]) -> String {
+    let map = render_map(dots, folds);
 
-    map.into_iter().fold(0, |sum, row| {
-        sum + row.into_iter().filter(|d| d.is_some()).count()
-    })
+    read_letters(&map).unwrap_or(map)
 }
 
-fn output_map(input: Vec<String>) -> Result<(), ()> {
-    let map = fold_paper(input, None);
-    let output: Vec<String> = map
-        .into_iter()
-        .map(|row| {
-            row.into_iter()
-                .map(|d| if d.is_some() { "#" } else { "." })
-                .collect::<String>()
-        })
-        .collect();
+pub struct DayThirteen;
+
+impl Solution for DayThirteen {
+    const DAY: usize = 13;
+
+    type Input = (Vec<Dot>, Vec<Fold>);
+    type PartOne = usize;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input.lines())
+    }
+
+    fn part_one((dots, folds): &Self::Input) -> Result<Self::PartOne> {
+        Ok(count_visible_dots(dots, folds, 1))
+    }
 
-    println!("{}", output.join("\n"));
+    fn part_two((dots, folds): &Self::Input) -> Result<Self::PartTwo> {
+        Ok(read_code(dots, folds))
+    }
 
-    Ok(())
+    const PARAMETER: Option<&'static str> = Some("folds");
+
+    fn solve_with((dots, folds): &Self::Input, num_folds: usize) -> Result<String> {
+        Ok(count_visible_dots(dots, folds, num_folds).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    fn use_example_input() -> Vec<String> {
+    use super::{Dot, Fold, Paper};
+    use crate::Error;
+
+    fn use_example_lines() -> Vec<String> {
         String::from(
             r#"6,10
 0,14
@@ -188,46 +374,152 @@ fold along x=5"#,
         .collect()
     }
 
-    fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_thirteen.txt")
-            .lines()
-            .map(String::from)
-            .filter(|l| !l.is_empty())
-            .collect()
+    fn use_example_input() -> (Vec<Dot>, Vec<Fold>) {
+        super::parse_input(use_example_lines()).unwrap()
+    }
+
+    fn use_real_input() -> (Vec<Dot>, Vec<Fold>) {
+        super::parse_input(include_str!("../input/default/day_thirteen.txt").lines()).unwrap()
     }
 
     #[test]
     fn test_count_visible_dots_after_one_fold_with_example_input() {
-        let input = use_example_input();
+        let (dots, folds) = use_example_input();
         let expected = 17;
-        let actual = super::count_visible_dots(input, 1);
+        let actual = super::count_visible_dots(&dots, &folds, 1);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_fold_paper_with_observer() {
+        let (dots, folds) = use_example_input();
+        let mut actual = vec![];
+        let expected = vec![(1, (7, 11), 17), (2, (7, 5), 16)];
+        super::fold_paper_with_observer(&dots, &folds, None, &mut |fold, paper: &Paper| {
+            actual.push((fold, paper.dimensions(), paper.visible_dots()))
+        });
 
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn test_count_visible_dots_after_one_fold_with_real_input() {
-        let input = use_real_input();
+        let (dots, folds) = use_real_input();
         let expected = 666;
-        let actual = super::count_visible_dots(input, 1);
+        let actual = super::count_visible_dots(&dots, &folds, 1);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_read_code_after_all_folds_with_example_input() {
+        let (dots, folds) = use_example_input();
+        let expected = String::from("#####\n#...#\n#...#\n#...#\n#####\n.....\n.....");
+        let actual = super::read_code(&dots, &folds);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_read_code_after_all_folds_with_real_input() {
+        let (dots, folds) = use_real_input();
+        let expected = String::from("CJHAZHKU");
+        let actual = super::read_code(&dots, &folds);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_invalid_fold() {
+        let mut input = use_example_lines();
+        input[18] = String::from("fold along z=7");
+        let expected = Err(Error::parse(13, "z", "unknown axis")
+            .at_line(19)
+            .at_column(12));
+        let actual = super::parse_input(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_invalid_dot() {
+        let mut input = use_example_lines();
+        input[2] = String::from("9,-10");
+        let expected = Err(Error::parse(13, "-10", "invalid coordinate")
+            .at_line(3)
+            .at_column(3));
+        let actual = super::parse_input(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_dot_too_large() {
+        let mut input = use_example_lines();
+        input[2] = String::from("9,1000000000");
+        let expected = Err(Error::parse(13, "1000000000", "coordinate is too large")
+            .at_line(3)
+            .at_column(3));
+        let actual = super::parse_input(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_fold_too_large() {
+        let mut input = use_example_lines();
+        input[18] = String::from("fold along y=18446744073709551615");
+        let expected = Err(
+            Error::parse(13, "18446744073709551615", "fold line is too large")
+                .at_line(19)
+                .at_column(14),
+        );
+        let actual = super::parse_input(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serialise_dots_and_folds() {
+        let parsed = super::parse_input(["6,10", "0,14", "", "fold along y=7"]).unwrap();
+        let expected = serde_json::json!([
+            [{"x": 6, "y": 10}, {"x": 0, "y": 14}],
+            [{"Y": 7}],
+        ]);
+        let actual = serde_json::to_value(&parsed).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_fold() {
+        let expected = Fold::Y(7);
+        let actual = serde_json::from_str::<Fold>(r#"{"Y": 7}"#).unwrap();
 
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn test_count_visible_dots_after_all_folds_with_example_input() {
-        let input = use_example_input();
-        let expected = Ok(()); // TODO: this should be "O"
-        let actual = super::output_map(input);
+    #[cfg(feature = "serde")]
+    fn test_deserialise_fold_too_large() {
+        let expected = "fold line 5000 is too large";
+        let actual = serde_json::from_str::<Fold>(r#"{"X": 5000}"#)
+            .unwrap_err()
+            .to_string();
 
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn test_count_visible_dots_after_all_folds_with_real_input() {
-        let input = use_real_input();
-        let expected = Ok(()); // TODO: this should be "CJHAZHKU"
-        let actual = super::output_map(input);
+    #[cfg(feature = "serde")]
+    fn test_deserialise_fold_along_the_edge() {
+        let expected = "invalid fold line 0";
+        let actual = serde_json::from_str::<Fold>(r#"{"Y": 0}"#)
+            .unwrap_err()
+            .to_string();
 
         assert_eq!(expected, actual);
     }
@@ -1,3 +1,5 @@
+use crate::{process_results, solve_collected, Error, Part, Result, Solution};
+
 fn filter_candidates(candidates: Vec<String>, prefix: &str) -> Vec<String> {
     candidates
         .into_iter()
@@ -9,7 +11,7 @@ fn is_column_one(column: usize) -> impl FnMut(&&String) -> bool {
     move |diagnostic: &&String| diagnostic.get(column..column + 1).unwrap_or_default() == "1"
 }
 
-fn majority_one(candidates: &Vec<String>, column: usize, threshold: usize, allow_eq: bool) -> bool {
+fn majority_one(candidates: &[String], column: usize, threshold: usize, allow_eq: bool) -> bool {
     let frequency = candidates.iter().filter(is_column_one(column)).count();
 
     if allow_eq {
@@ -19,27 +21,49 @@ fn majority_one(candidates: &Vec<String>, column: usize, threshold: usize, allow
     }
 }
 
-fn calculate_power_consumption(diagnostics: &Vec<String>) -> u64 {
-    let threshold = diagnostics.len() / 2;
-    let width = diagnostics.get(0).map(|d| d.len()).unwrap_or_default();
-    let shift = 64 - width;
+/// Only the number of ones in each column is kept, so `diagnostics` may be streamed.
+pub(crate) fn calculate_power_consumption(
+    diagnostics: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Result<u64> {
+    let mut ones: Vec<usize> = Vec::new();
+    let mut count = 0;
+
+    for diagnostic in diagnostics {
+        let diagnostic = diagnostic.as_ref();
+
+        if count == 0 {
+            ones = vec![0; diagnostic.len()];
+        }
+
+        for (ones, bit) in ones.iter_mut().zip(diagnostic.bytes()) {
+            *ones += usize::from(bit == b'1');
+        }
 
-    let most_common_value =
-        move |g, c| (g << 1) + u64::from(majority_one(diagnostics, c, threshold, false));
+        count += 1;
+    }
 
-    let gamma = (0..width).fold(0, most_common_value);
+    if ones.is_empty() {
+        return Ok(0);
+    }
+
+    let threshold = count / 2;
+    let shift = 64 - ones.len();
+
+    let gamma = ones
+        .iter()
+        .fold(0, |g, ones| (g << 1) + u64::from(*ones > threshold));
     let epsilon = (!(gamma << shift)) >> shift;
 
-    gamma * epsilon
+    gamma.checked_mul(epsilon).ok_or_else(too_large)
 }
 
-fn calculate_life_support_rating(diagnostics: &Vec<String>) -> u64 {
-    let width = diagnostics.get(0).map(|d| d.len()).unwrap_or_default();
+pub(crate) fn calculate_life_support_rating(diagnostics: &[String]) -> Result<u64> {
+    let width = diagnostics.first().map(|d| d.len()).unwrap_or_default();
 
     let first_o2_digit = u64::from(majority_one(
-        &diagnostics,
+        diagnostics,
         0,
-        (diagnostics.len() + 1) / 2,
+        diagnostics.len().div_ceil(2),
         true,
     ));
 
@@ -49,18 +73,18 @@ fn calculate_life_support_rating(diagnostics: &Vec<String>) -> u64 {
     let mut o2 = first_o2_digit.to_string();
     o2.reserve(width - 1);
 
-    let mut o2_candidates: Vec<String> = filter_candidates(diagnostics.clone(), &o2);
-    let mut o2_threshold = (o2_candidates.len() + 1) / 2;
+    let mut o2_candidates: Vec<String> = filter_candidates(diagnostics.to_vec(), &o2);
+    let mut o2_threshold = o2_candidates.len().div_ceil(2);
 
-    let mut co2_candidates: Vec<String> = filter_candidates(diagnostics.clone(), &co2);
-    let mut co2_threshold = (co2_candidates.len() + 1) / 2;
+    let mut co2_candidates: Vec<String> = filter_candidates(diagnostics.to_vec(), &co2);
+    let mut co2_threshold = co2_candidates.len().div_ceil(2);
 
     for col in 1..width {
         if o2_candidates.len() > 1 {
             let digit = u64::from(majority_one(&o2_candidates, col, o2_threshold, true));
             o2.push_str(digit.to_string().as_str());
             o2_candidates = filter_candidates(o2_candidates, &o2);
-            o2_threshold = (o2_candidates.len() + 1) / 2;
+            o2_threshold = o2_candidates.len().div_ceil(2);
         } else if o2_candidates.len() == 1 {
             o2 = o2_candidates.remove(0);
         }
@@ -69,20 +93,120 @@ fn calculate_life_support_rating(diagnostics: &Vec<String>) -> u64 {
             let digit = u64::from(!majority_one(&co2_candidates, col, co2_threshold, true));
             co2.push_str(digit.to_string().as_str());
             co2_candidates = filter_candidates(co2_candidates, &co2);
-            co2_threshold = (co2_candidates.len() + 1) / 2;
+            co2_threshold = co2_candidates.len().div_ceil(2);
         } else if co2_candidates.len() == 1 {
             co2 = co2_candidates.remove(0);
         }
     }
 
-    u64::from_str_radix(&o2, 2).unwrap_or_default()
-        * u64::from_str_radix(&co2, 2).unwrap_or_default()
+    u64::from_str_radix(&o2, 2)
+        .unwrap_or_default()
+        .checked_mul(u64::from_str_radix(&co2, 2).unwrap_or_default())
+        .ok_or_else(too_large)
+}
+
+fn too_large() -> Error {
+    Error::unsolvable(DayThree::DAY, "rating is too large")
+}
+
+fn no_diagnostics() -> Error {
+    Error::unsolvable(
+        DayThree::DAY,
+        "diagnostics must be between 1 and 64 bits wide",
+    )
+}
+
+/// Checks each diagnostic in turn is made up of bits, and as wide as the first.
+fn validate_diagnostics<I, S>(lines: I) -> impl Iterator<Item = Result<S>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut width = None;
+
+    lines.into_iter().enumerate().map(move |(ix, line)| {
+        let diagnostic = line.as_ref();
+        let width = *width.get_or_insert(diagnostic.len());
+
+        if width == 0 || width > 64 {
+            return Err(no_diagnostics());
+        }
+
+        if let Some(column) = diagnostic.find(|c| c != '0' && c != '1') {
+            return Err(Error::parse(DayThree::DAY, diagnostic, "invalid bit")
+                .at_line(ix + 1)
+                .at_column(column + 1));
+        }
+
+        if diagnostic.len() != width {
+            return Err(Error::parse(
+                DayThree::DAY,
+                diagnostic,
+                format!("expected {} bits", width),
+            )
+            .at_line(ix + 1));
+        }
+
+        Ok(line)
+    })
+}
+
+fn parse_input(input: &str) -> Result<Vec<String>> {
+    let diagnostics: Vec<String> =
+        validate_diagnostics(input.lines().map(String::from)).collect::<Result<_>>()?;
+
+    if diagnostics.is_empty() {
+        return Err(no_diagnostics());
+    }
+
+    Ok(diagnostics)
+}
+
+pub struct DayThree;
+
+impl Solution for DayThree {
+    const DAY: usize = 3;
+
+    type Input = Vec<String>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        calculate_power_consumption(input)
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        calculate_life_support_rating(input)
+    }
+
+    fn solve_lines(lines: &mut dyn Iterator<Item = String>, part: Part) -> Result<String> {
+        match part {
+            Part::One => process_results(validate_diagnostics(lines), |diagnostics| {
+                let mut diagnostics = diagnostics.peekable();
+
+                match diagnostics.peek() {
+                    Some(_) => {
+                        calculate_power_consumption(diagnostics).map(|answer| answer.to_string())
+                    }
+                    None => Err(no_diagnostics()),
+                }
+            })
+            .and_then(|answer| answer),
+            Part::Two => solve_collected::<Self>(lines, part),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::Error;
+
     fn read_diagnostics_from_input_file() -> Vec<String> {
-        include_str!("../input/day_three.txt")
+        include_str!("../input/default/day_three.txt")
             .to_owned()
             .lines()
             .map(String::from)
@@ -106,7 +230,7 @@ mod tests {
             String::from("01010"),
         ];
 
-        let expected = 198;
+        let expected = Ok(198);
         let actual = super::calculate_power_consumption(&diagnostics);
 
         assert_eq!(expected, actual);
@@ -115,7 +239,7 @@ mod tests {
     #[test]
     fn test_calculate_power_consumption_with_real_input() {
         let diagnostics = read_diagnostics_from_input_file();
-        let expected = 2583164;
+        let expected = Ok(2583164);
         let actual = super::calculate_power_consumption(&diagnostics);
 
         assert_eq!(expected, actual);
@@ -138,7 +262,7 @@ mod tests {
             String::from("01010"),
         ];
 
-        let expected = 230;
+        let expected = Ok(230);
         let actual = super::calculate_life_support_rating(&diagnostics);
 
         assert_eq!(expected, actual);
@@ -147,9 +271,36 @@ mod tests {
     #[test]
     fn test_calculate_life_support_rating_with_real_input() {
         let diagnostics = read_diagnostics_from_input_file();
-        let expected = 2784375;
+        let expected = Ok(2784375);
         let actual = super::calculate_life_support_rating(&diagnostics);
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_parse_input_with_invalid_bit() {
+        let expected = Err(Error::parse(3, "10201", "invalid bit")
+            .at_line(2)
+            .at_column(3));
+        let actual = super::parse_input("00100\n10201");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_ragged_rows() {
+        let expected = Err(Error::parse(3, "", "expected 5 bits").at_line(2));
+        let actual = super::parse_input("00100\n\n11110");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_power_consumption_with_rating_too_large() {
+        let diagnostics = vec![format!("{}{}", "1".repeat(32), "0".repeat(32))];
+        let expected = Err(Error::unsolvable(3, "rating is too large"));
+        let actual = super::calculate_power_consumption(&diagnostics);
+
+        assert_eq!(expected, actual);
+    }
 }
@@ -1,26 +1,37 @@
+use crate::{error::column_of, Error, Result, Solution};
 use std::{collections::HashMap, str::FromStr};
 
-#[derive(Clone, Copy)]
-enum CaveSize {
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum CaveSize {
     Big,
     Small,
 }
 
-#[derive(Clone)]
-struct Cave {
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Cave {
     id: String,
     links: Vec<String>,
     size: CaveSize,
 }
 
 impl FromStr for Cave {
-    type Err = ();
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let size = if s == s.to_uppercase() {
+    fn from_str(s: &str) -> Result<Self> {
+        let size = if s.is_empty() || !s.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(Error::parse(DayTwelve::DAY, s, "invalid cave"));
+        } else if s == s.to_uppercase() {
             CaveSize::Big
-        } else {
+        } else if s == s.to_lowercase() {
             CaveSize::Small
+        } else {
+            return Err(Error::parse(
+                DayTwelve::DAY,
+                s,
+                "cave is neither big nor small",
+            ));
         };
 
         Ok(Self {
@@ -32,7 +43,7 @@ impl FromStr for Cave {
 }
 
 impl Cave {
-    fn valid_next_caves<P>(&self, path: &Vec<String>) -> Option<Vec<&String>>
+    fn valid_next_caves<P>(&self, path: &[String]) -> Option<Vec<&String>>
     where
         P: Path,
     {
@@ -50,22 +61,22 @@ impl Cave {
     }
 }
 
-trait Path {
-    fn is_valid_next_cave(id: &String, path: &Vec<String>) -> bool;
+pub(crate) trait Path {
+    fn is_valid_next_cave(id: &str, path: &[String]) -> bool;
 }
 
-struct SimplePath;
+pub(crate) struct SimplePath;
 
 impl Path for SimplePath {
-    fn is_valid_next_cave(id: &String, path: &Vec<String>) -> bool {
-        *id == id.to_uppercase() || !path.contains(id)
+    fn is_valid_next_cave(id: &str, path: &[String]) -> bool {
+        id == id.to_uppercase() || !path.iter().any(|visited| visited == id)
     }
 }
 
-struct ComplexPath;
+pub(crate) struct ComplexPath;
 
 impl Path for ComplexPath {
-    fn is_valid_next_cave(id: &String, path: &Vec<String>) -> bool {
+    fn is_valid_next_cave(id: &str, path: &[String]) -> bool {
         if SimplePath::is_valid_next_cave(id, path) {
             return true;
         }
@@ -84,88 +95,197 @@ impl Path for ComplexPath {
     }
 }
 
-fn parse_input(input: Vec<String>) -> HashMap<String, Cave> {
-    input.into_iter().fold(HashMap::new(), |mut caves, line| {
-        let (a, b) = line.split_once('-').unwrap_or_default();
+fn parse_link(line: &str) -> Result<(Cave, Cave)> {
+    let (a, b) = line
+        .split_once('-')
+        .ok_or_else(|| Error::parse(DayTwelve::DAY, line, "expected a link `a-b`"))?;
+
+    let a = a.parse::<Cave>().map_err(|e| e.at_column(1))?;
+    let b = b
+        .parse::<Cave>()
+        .map_err(|e| e.at_column(column_of(line, b)))?;
+
+    if let (CaveSize::Big, CaveSize::Big) = (a.size, b.size) {
+        // two linked big caves could be visited back and forth forever
+        return Err(Error::parse(
+            DayTwelve::DAY,
+            line,
+            "big caves cannot be linked",
+        ));
+    }
+
+    Ok((a, b))
+}
 
-        let cave = caves
-            .entry(a.to_string())
-            .or_insert(a.parse::<Cave>().unwrap());
+/// Every cave, keyed by name, with the caves it leads to.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(try_from = "HashMap<String, Cave>")
+)]
+pub struct Caves(HashMap<String, Cave>);
+
+impl Caves {
+    fn get(&self, id: &str) -> Option<&Cave> {
+        self.0.get(id)
+    }
+}
 
-        if a != "end" && b != "start" {
-            cave.links.push(b.to_string());
+#[cfg(feature = "serde")]
+impl TryFrom<HashMap<String, Cave>> for Caves {
+    type Error = String;
+
+    /// Accepts the caves only if they could have come from [`parse_input`]: each is keyed by a
+    /// valid name matching its size, and every link names an existing cave, is linked back unless
+    /// it leaves `start` or reaches `end`, and never joins two big caves.
+    fn try_from(caves: HashMap<String, Cave>) -> std::result::Result<Self, Self::Error> {
+        for (id, cave) in caves.iter() {
+            let named = id
+                .parse::<Cave>()
+                .map_err(|_| format!("invalid cave {}", id))?;
+
+            if *id != cave.id || named.size != cave.size {
+                return Err(format!("cave {} does not match its name", id));
+            }
+
+            for link in cave.links.iter() {
+                let other = caves
+                    .get(link)
+                    .ok_or_else(|| format!("cave {} links to missing cave {}", id, link))?;
+
+                if id == "end" || link == "start" {
+                    return Err(format!("cave {} cannot lead to {}", id, link));
+                }
+
+                if id != "start" && link != "end" && !other.links.contains(id) {
+                    return Err(format!("cave {} does not link back to {}", link, id));
+                }
+
+                if let (CaveSize::Big, CaveSize::Big) = (cave.size, other.size) {
+                    return Err(format!("big caves {} and {} cannot be linked", id, link));
+                }
+            }
         }
 
-        let cave = caves
-            .entry(b.to_string())
-            .or_insert(b.parse::<Cave>().unwrap());
+        Ok(Self(caves))
+    }
+}
 
-        if b != "end" && a != "start" {
-            cave.links.push(a.to_string());
-        }
+/// Parses the links between caves into every cave, keyed by name, with the caves it leads to.
+pub fn parse_input(input: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Caves> {
+    input
+        .into_iter()
+        .enumerate()
+        .try_fold(HashMap::new(), |mut caves, (ix, line)| {
+            let (a, b) = parse_link(line.as_ref()).map_err(|e| e.at_line(ix + 1))?;
+            let (a_id, b_id) = (a.id.clone(), b.id.clone());
+
+            let cave = caves.entry(a_id.clone()).or_insert(a);
+
+            if a_id != "end" && b_id != "start" {
+                cave.links.push(b_id.clone());
+            }
 
-        caves
-    })
+            let cave = caves.entry(b_id.clone()).or_insert(b);
+
+            if b_id != "end" && a_id != "start" {
+                cave.links.push(a_id);
+            }
+
+            Ok(caves)
+        })
+        .map(Caves)
 }
 
 fn find_path<P>(
     current: &Cave,
     mut path: Vec<String>,
     paths: &mut Vec<Vec<String>>,
-    caves: &HashMap<String, Cave>,
-) where
+    caves: &Caves,
+) -> Result<()>
+where
     P: Path,
 {
     path.push(current.id.clone());
 
     if current.id.as_str() == "end" {
         paths.push(path);
-        return;
+        return Ok(());
     }
 
     if let Some(links) = current.valid_next_caves::<P>(&path) {
         for link in links.iter() {
-            let next = caves.get(*link).unwrap();
-            find_path::<P>(next, path.clone(), paths, caves);
+            let next = caves.get(link).ok_or_else(|| {
+                Error::unsolvable(
+                    DayTwelve::DAY,
+                    format!("cave {} leads to missing cave {}", current.id, link),
+                )
+            })?;
+            find_path::<P>(next, path.clone(), paths, caves)?;
         }
     }
+
+    Ok(())
 }
 
-fn enumerate_paths<P>(input: Vec<String>) -> Vec<String>
+pub(crate) fn enumerate_paths<P>(caves: &Caves) -> Result<Vec<String>>
 where
     P: Path,
 {
-    let caves = parse_input(input);
-
-    let start = caves.get("start").unwrap();
+    let start = caves
+        .get("start")
+        .ok_or_else(|| Error::unsolvable(DayTwelve::DAY, "there is no start cave"))?;
     let path = vec![];
     let mut paths = vec![];
 
-    find_path::<P>(start, path, &mut paths, &caves);
-    paths.into_iter().map(|path| path.join(",")).collect()
+    find_path::<P>(start, path, &mut paths, caves)?;
+    Ok(paths.into_iter().map(|path| path.join(",")).collect())
+}
+
+pub struct DayTwelve;
+
+impl Solution for DayTwelve {
+    const DAY: usize = 12;
+
+    type Input = Caves;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input.lines())
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        enumerate_paths::<SimplePath>(input).map(|paths| paths.len())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        enumerate_paths::<ComplexPath>(input).map(|paths| paths.len())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ComplexPath, SimplePath};
+    use super::{Caves, ComplexPath, SimplePath};
+    use crate::Error;
 
-    fn use_smallest_example_input() -> Vec<String> {
-        String::from(
+    fn use_smallest_example_input() -> Caves {
+        super::parse_input(
             r#"start-A
 start-b
 A-c
 A-b
 b-d
 A-end
-b-end"#,
+b-end"#
+                .lines(),
         )
-        .lines()
-        .map(String::from)
-        .collect()
+        .unwrap()
     }
 
-    fn use_small_example_input() -> Vec<String> {
-        String::from(
+    fn use_small_example_input() -> Caves {
+        super::parse_input(
             r#"dc-end
 HN-start
 start-kj
@@ -175,15 +295,14 @@ LN-dc
 HN-end
 kj-sa
 kj-HN
-kj-dc"#,
+kj-dc"#
+                .lines(),
         )
-        .lines()
-        .map(String::from)
-        .collect()
+        .unwrap()
     }
 
-    fn use_example_input() -> Vec<String> {
-        String::from(
+    fn use_example_input() -> Caves {
+        super::parse_input(
             r#"fs-end
 he-DX
 fs-he
@@ -201,89 +320,194 @@ start-pj
 he-WI
 zg-he
 pj-fs
-start-RW"#,
+start-RW"#
+                .lines(),
         )
-        .lines()
-        .map(String::from)
-        .collect()
+        .unwrap()
     }
 
-    fn use_real_input() -> Vec<String> {
-        include_str!("../input/day_twelve.txt")
-            .lines()
-            .map(String::from)
-            .collect()
+    fn use_real_input() -> Caves {
+        super::parse_input(include_str!("../input/default/day_twelve.txt").lines()).unwrap()
     }
 
     #[test]
     fn test_enumerate_simple_paths_with_smallest_example_input() {
         let input = use_smallest_example_input();
         let expected = 10;
-        let actual = super::enumerate_paths::<SimplePath>(input);
+        let actual = super::enumerate_paths::<SimplePath>(&input);
 
-        assert_eq!(expected, actual.len());
+        assert_eq!(Ok(expected), actual.map(|paths| paths.len()));
     }
 
     #[test]
     fn test_enumerate_simple_paths_with_small_example_input() {
         let input = use_small_example_input();
         let expected = 19;
-        let actual = super::enumerate_paths::<SimplePath>(input);
+        let actual = super::enumerate_paths::<SimplePath>(&input);
 
-        assert_eq!(expected, actual.len());
+        assert_eq!(Ok(expected), actual.map(|paths| paths.len()));
     }
 
     #[test]
     fn test_enumerate_simple_paths_with_example_input() {
         let input = use_example_input();
         let expected = 226;
-        let actual = super::enumerate_paths::<SimplePath>(input);
+        let actual = super::enumerate_paths::<SimplePath>(&input);
 
-        assert_eq!(expected, actual.len());
+        assert_eq!(Ok(expected), actual.map(|paths| paths.len()));
     }
 
     #[test]
     fn test_enumerate_simple_paths_with_real_input() {
         let input = use_real_input();
         let expected = 4186;
-        let actual = super::enumerate_paths::<SimplePath>(input);
+        let actual = super::enumerate_paths::<SimplePath>(&input);
 
-        assert_eq!(expected, actual.len());
+        assert_eq!(Ok(expected), actual.map(|paths| paths.len()));
     }
 
     #[test]
     fn test_enumerate_complex_paths_with_smallest_example_input() {
         let input = use_smallest_example_input();
         let expected = 36;
-        let actual = super::enumerate_paths::<ComplexPath>(input);
+        let actual = super::enumerate_paths::<ComplexPath>(&input);
 
-        assert_eq!(expected, actual.len());
+        assert_eq!(Ok(expected), actual.map(|paths| paths.len()));
     }
 
     #[test]
     fn test_enumerate_complex_paths_with_small_example_input() {
         let input = use_small_example_input();
         let expected = 103;
-        let actual = super::enumerate_paths::<ComplexPath>(input);
+        let actual = super::enumerate_paths::<ComplexPath>(&input);
 
-        assert_eq!(expected, actual.len());
+        assert_eq!(Ok(expected), actual.map(|paths| paths.len()));
     }
 
     #[test]
     fn test_enumerate_complex_paths_with_example_input() {
         let input = use_example_input();
         let expected = 3509;
-        let actual = super::enumerate_paths::<ComplexPath>(input);
+        let actual = super::enumerate_paths::<ComplexPath>(&input);
 
-        assert_eq!(expected, actual.len());
+        assert_eq!(Ok(expected), actual.map(|paths| paths.len()));
     }
 
     #[test]
     fn test_enumerate_complex_paths_with_real_input() {
         let input = use_real_input();
         let expected = 92111;
-        let actual = super::enumerate_paths::<ComplexPath>(input);
+        let actual = super::enumerate_paths::<ComplexPath>(&input);
+
+        assert_eq!(Ok(expected), actual.map(|paths| paths.len()));
+    }
+
+    #[test]
+    fn test_parse_input_with_linked_big_caves() {
+        let input = [
+            "start-A", "start-b", "A-c", "A-b", "b-d", "A-end", "b-end", "A-B",
+        ];
+        let expected = Err(Error::parse(12, "A-B", "big caves cannot be linked").at_line(8));
+        let actual = super::parse_input(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_input_with_invalid_cave() {
+        let input = ["start-A", "start-b", "A-c", "A-bB", "b-d", "A-end", "b-end"];
+        let expected = Err(Error::parse(12, "bB", "cave is neither big nor small")
+            .at_line(4)
+            .at_column(3));
+        let actual = super::parse_input(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_enumerate_paths_without_start_cave() {
+        let input = super::parse_input(["A-b", "b-end"]).unwrap();
+        let expected = Err(Error::unsolvable(12, "there is no start cave"));
+        let actual = super::enumerate_paths::<SimplePath>(&input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serialise_caves() {
+        let caves = super::parse_input(["start-A", "A-end"]).unwrap();
+        let expected = serde_json::json!({
+            "start": {"id": "start", "links": ["A"], "size": "Small"},
+            "A": {"id": "A", "links": ["end"], "size": "Big"},
+            "end": {"id": "end", "links": [], "size": "Small"},
+        });
+        let actual = serde_json::to_value(&caves).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_caves() {
+        let json = r#"{
+            "start": {"id": "start", "links": ["A"], "size": "Small"},
+            "A": {"id": "A", "links": ["end"], "size": "Big"},
+            "end": {"id": "end", "links": [], "size": "Small"}
+        }"#;
+        let expected = super::parse_input(["start-A", "A-end"]).unwrap();
+        let actual = serde_json::from_str::<Caves>(json).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_caves_with_missing_link() {
+        let json = r#"{
+            "start": {"id": "start", "links": ["zz"], "size": "Small"},
+            "end": {"id": "end", "links": [], "size": "Small"}
+        }"#;
+        let expected = "cave start links to missing cave zz";
+        let actual = serde_json::from_str::<Caves>(json).unwrap_err().to_string();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_caves_without_link_back() {
+        let json = r#"{
+            "start": {"id": "start", "links": ["a"], "size": "Small"},
+            "a": {"id": "a", "links": ["b"], "size": "Small"},
+            "b": {"id": "b", "links": ["end"], "size": "Small"},
+            "end": {"id": "end", "links": [], "size": "Small"}
+        }"#;
+        let expected = "cave b does not link back to a";
+        let actual = serde_json::from_str::<Caves>(json).unwrap_err().to_string();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_caves_with_linked_big_caves() {
+        let json = r#"{
+            "A": {"id": "A", "links": ["B"], "size": "Big"},
+            "B": {"id": "B", "links": ["A"], "size": "Big"}
+        }"#;
+        let actual = serde_json::from_str::<Caves>(json).unwrap_err().to_string();
+
+        assert!(actual.starts_with("big caves "));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_caves_with_mismatched_size() {
+        let json = r#"{"a": {"id": "a", "links": [], "size": "Big"}}"#;
+        let expected = "cave a does not match its name";
+        let actual = serde_json::from_str::<Caves>(json).unwrap_err().to_string();
 
-        assert_eq!(expected, actual.len());
+        assert_eq!(expected, actual);
     }
 }
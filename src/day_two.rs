@@ -1,125 +1,184 @@
-use std::{ops::Add, str::FromStr};
+use crate::{process_results, Error, Part, Result, Solution};
+use std::{borrow::Borrow, str::FromStr};
 
-#[derive(Clone, Copy, Debug)]
-enum Command {
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Command {
     Down(isize),
     Forward(isize),
     Up(isize),
 }
 
-#[derive(Clone, Debug)]
-enum CommandError {
-    InvalidCommand(String),
-}
-
 impl FromStr for Command {
-    type Err = CommandError;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         let (tag, units) = s
             .split_once(' ')
-            .ok_or(CommandError::InvalidCommand(s.to_owned()))?;
+            .ok_or_else(|| Error::parse(DayTwo::DAY, s, "expected a direction and units"))?;
 
-        let units = units
-            .parse()
-            .map_err(|_| CommandError::InvalidCommand(s.to_owned()))?;
+        let units = units.parse().map_err(|_| {
+            Error::parse(DayTwo::DAY, units, "invalid units").at_column(tag.len() + 2)
+        })?;
 
         match tag {
             "down" => Ok(Command::Down(units)),
             "forward" => Ok(Command::Forward(units)),
             "up" => Ok(Command::Up(units)),
-            _ => Err(CommandError::InvalidCommand(s.to_owned())),
+            _ => Err(Error::parse(DayTwo::DAY, tag, "unknown direction").at_column(1)),
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-struct SimplePosition {
-    depth: isize,
-    horizontal: isize,
+/// A position the submarine can be steered from.
+pub(crate) trait Steer: Default + Sized {
+    /// Returns the position after following `command`, or `None` if it is out of range.
+    fn steer(self, command: Command) -> Option<Self>;
+
+    /// Returns the horizontal position multiplied by the depth.
+    fn product(&self) -> Result<isize>;
+}
+
+fn out_of_range() -> Error {
+    Error::unsolvable(DayTwo::DAY, "position is out of range")
 }
 
-impl Add<Command> for SimplePosition {
-    type Output = Self;
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SimplePosition {
+    pub(crate) depth: isize,
+    pub(crate) horizontal: isize,
+}
 
-    fn add(self, command: Command) -> Self::Output {
+impl Steer for SimplePosition {
+    fn steer(self, command: Command) -> Option<Self> {
         match command {
-            Command::Down(units) => Self {
+            Command::Down(units) => Some(Self {
                 horizontal: self.horizontal,
-                depth: self.depth + units,
-            },
-            Command::Forward(units) => Self {
-                horizontal: self.horizontal + units,
+                depth: self.depth.checked_add(units)?,
+            }),
+            Command::Forward(units) => Some(Self {
+                horizontal: self.horizontal.checked_add(units)?,
                 depth: self.depth,
-            },
-            Command::Up(units) => Self {
+            }),
+            Command::Up(units) => Some(Self {
                 horizontal: self.horizontal,
-                depth: self.depth - units,
-            },
+                depth: self.depth.checked_sub(units)?,
+            }),
         }
     }
+
+    fn product(&self) -> Result<isize> {
+        self.horizontal
+            .checked_mul(self.depth)
+            .ok_or_else(out_of_range)
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default)]
-struct AimedPosition {
+pub(crate) struct AimedPosition {
     aim: isize,
-    depth: isize,
-    horizontal: isize,
+    pub(crate) depth: isize,
+    pub(crate) horizontal: isize,
 }
 
-impl Add<Command> for AimedPosition {
-    type Output = Self;
-
-    fn add(self, command: Command) -> Self::Output {
+impl Steer for AimedPosition {
+    fn steer(self, command: Command) -> Option<Self> {
         match command {
-            Command::Down(units) => Self {
-                aim: self.aim + units,
+            Command::Down(units) => Some(Self {
+                aim: self.aim.checked_add(units)?,
                 horizontal: self.horizontal,
                 depth: self.depth,
-            },
-            Command::Forward(units) => Self {
+            }),
+            Command::Forward(units) => Some(Self {
                 aim: self.aim,
-                horizontal: self.horizontal + units,
-                depth: self.depth + self.aim * units,
-            },
-            Command::Up(units) => Self {
-                aim: self.aim - units,
+                horizontal: self.horizontal.checked_add(units)?,
+                depth: self.depth.checked_add(self.aim.checked_mul(units)?)?,
+            }),
+            Command::Up(units) => Some(Self {
+                aim: self.aim.checked_sub(units)?,
                 horizontal: self.horizontal,
                 depth: self.depth,
-            },
+            }),
         }
     }
+
+    fn product(&self) -> Result<isize> {
+        self.horizontal
+            .checked_mul(self.depth)
+            .ok_or_else(out_of_range)
+    }
 }
 
-fn calculate_position<P>(commands: &str) -> P
+fn parse_commands<I, S>(lines: I) -> impl Iterator<Item = Result<Command>>
 where
-    P: Default + Add<Command, Output = P>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
 {
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(ix, line)| line.as_ref().parse().map_err(|e: Error| e.at_line(ix + 1)))
+}
+
+/// Only the current position is held at any time, so `commands` may be streamed.
+pub(crate) fn calculate_position<P: Steer>(
+    commands: impl IntoIterator<Item = impl Borrow<Command>>,
+) -> Result<P> {
     commands
-        .lines()
-        .filter_map(|value| value.parse::<Command>().ok())
-        .fold(P::default(), |position, command| position + command)
+        .into_iter()
+        .try_fold(P::default(), |position, command| {
+            position.steer(*command.borrow()).ok_or_else(out_of_range)
+        })
+}
+
+pub struct DayTwo;
+
+impl Solution for DayTwo {
+    const DAY: usize = 2;
+
+    type Input = Vec<Command>;
+    type PartOne = isize;
+    type PartTwo = isize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_commands(input.lines()).collect()
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        calculate_position::<SimplePosition>(input)?.product()
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        calculate_position::<AimedPosition>(input)?.product()
+    }
+
+    fn solve_lines(lines: &mut dyn Iterator<Item = String>, part: Part) -> Result<String> {
+        let position = process_results(parse_commands(lines), |commands| match part {
+            Part::One => calculate_position::<SimplePosition>(commands)?.product(),
+            Part::Two => calculate_position::<AimedPosition>(commands)?.product(),
+        })?;
+
+        position.map(|answer| answer.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day_two::{AimedPosition, SimplePosition};
+    use crate::day_two::{AimedPosition, Command, DayTwo, SimplePosition};
+    use crate::{Error, Result, Solution};
+
+    fn use_example_input() -> Vec<Command> {
+        DayTwo::parse("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2").unwrap()
+    }
 
-    fn read_commands_from_input_file() -> String {
-        include_str!("../input/day_two.txt").to_owned()
+    fn read_commands_from_input_file() -> Vec<Command> {
+        DayTwo::parse(include_str!("../input/default/day_two.txt")).unwrap()
     }
 
     #[test]
     fn test_calculate_position_with_example_input() {
         let expected = 150;
-        let actual = super::calculate_position::<SimplePosition>(
-            r#"forward 5
-down 5
-forward 8
-up 3
-down 8
-forward 2"#,
-        );
+        let actual = super::calculate_position::<SimplePosition>(use_example_input()).unwrap();
 
         assert_eq!(expected, actual.horizontal * actual.depth);
     }
@@ -128,7 +187,7 @@ forward 2"#,
     fn test_calculate_position_with_real_input() {
         let commands = read_commands_from_input_file();
         let expected = 1727835;
-        let actual = super::calculate_position::<SimplePosition>(&commands);
+        let actual = super::calculate_position::<SimplePosition>(commands).unwrap();
 
         assert_eq!(expected, actual.horizontal * actual.depth);
     }
@@ -136,14 +195,7 @@ forward 2"#,
     #[test]
     fn test_calculate_position_with_example_input_for_sliding_window_of_three() {
         let expected = 900;
-        let actual = super::calculate_position::<AimedPosition>(
-            r#"forward 5
-down 5
-forward 8
-up 3
-down 8
-forward 2"#,
-        );
+        let actual = super::calculate_position::<AimedPosition>(use_example_input()).unwrap();
 
         assert_eq!(expected, actual.horizontal * actual.depth);
     }
@@ -152,8 +204,47 @@ forward 2"#,
     fn test_calculate_position_with_real_input_for_sliding_window_of_three() {
         let commands = read_commands_from_input_file();
         let expected = 1544000595;
-        let actual = super::calculate_position::<AimedPosition>(&commands);
+        let actual = super::calculate_position::<AimedPosition>(commands).unwrap();
 
         assert_eq!(expected, actual.horizontal * actual.depth);
     }
+
+    #[test]
+    fn test_parse_with_unknown_direction() {
+        let expected: Result<Vec<Command>> = Err(Error::parse(2, "sideways", "unknown direction")
+            .at_line(2)
+            .at_column(1));
+        let actual = DayTwo::parse("forward 5\nsideways 5");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_with_invalid_units() {
+        let expected: Result<Vec<Command>> = Err(Error::parse(2, "five", "invalid units")
+            .at_line(1)
+            .at_column(9));
+        let actual = DayTwo::parse("forward five");
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_calculate_position_out_of_range() {
+        let expected = Error::unsolvable(2, "position is out of range");
+        let commands = DayTwo::parse("down 5\nforward 9223372036854775807").unwrap();
+        let actual = super::calculate_position::<AimedPosition>(commands).err();
+
+        assert_eq!(Some(expected), actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serialise_command() {
+        let command: super::Command = "forward 5".parse().unwrap();
+        let expected = String::from(r#"{"Forward":5}"#);
+        let actual = serde_json::to_string(&command).unwrap();
+
+        assert_eq!(expected, actual);
+    }
 }
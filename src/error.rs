@@ -0,0 +1,208 @@
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An error raised while parsing or solving a day's puzzle input, or reading the answers expected
+/// of it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// Some `text` on the given (one-based) `line` of the puzzle input could not be parsed.
+    /// `column` is the one-based character offset of `text` within the line, where known.
+    Parse {
+        day: usize,
+        line: usize,
+        column: Option<usize>,
+        text: String,
+        reason: String,
+    },
+    /// The puzzle input was well formed, but no answer can be found from it.
+    Unsolvable { day: usize, reason: String },
+    /// The puzzle input could not be read from `source`, such as a file path or standard input.
+    Input {
+        day: usize,
+        source: String,
+        reason: String,
+    },
+    /// Some `text` on the given (one-based) `line` of an answers manifest could not be parsed.
+    Manifest {
+        line: usize,
+        text: String,
+        reason: String,
+    },
+}
+
+impl Error {
+    /// Creates a parse error for `text` found on the first line of the input. Parsers that only
+    /// see a single line or token should use this, leaving their caller to set the real position
+    /// with [`Error::at_line`] and [`Error::at_column`].
+    pub fn parse(day: usize, text: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Parse {
+            day,
+            line: 1,
+            column: None,
+            text: text.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn unsolvable(day: usize, reason: impl Into<String>) -> Self {
+        Self::Unsolvable {
+            day,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn input(day: usize, source: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Input {
+            day,
+            source: source.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn manifest(line: usize, text: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Manifest {
+            line,
+            text: text.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Moves a parse error to the given one-based `line`. Other errors are returned unchanged.
+    pub fn at_line(mut self, line: usize) -> Self {
+        if let Self::Parse {
+            line: ref mut l, ..
+        } = self
+        {
+            *l = line;
+        }
+
+        self
+    }
+
+    /// Moves a parse error to the given one-based `column`, or further along the line if the error
+    /// already has a column relative to some token starting at `column`. Other errors are returned
+    /// unchanged.
+    pub fn at_column(mut self, column: usize) -> Self {
+        if let Self::Parse {
+            column: ref mut c, ..
+        } = self
+        {
+            *c = Some(c.map_or(column, |offset| column + offset - 1));
+        }
+
+        self
+    }
+
+    /// Returns the day the error was raised for, or `None` if it is not specific to one day.
+    pub fn day(&self) -> Option<usize> {
+        match self {
+            Self::Parse { day, .. } | Self::Unsolvable { day, .. } | Self::Input { day, .. } => {
+                Some(*day)
+            }
+            Self::Manifest { .. } => None,
+        }
+    }
+}
+
+/// Returns the one-based column at which `token`, which must be a subslice of `line`, starts.
+pub(crate) fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+    line.get(..offset)
+        .map_or(0, |prefix| prefix.chars().count())
+        + 1
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse {
+                day,
+                line,
+                column: Some(column),
+                text,
+                reason,
+            } => write!(
+                f,
+                "day {}, line {}, column {}: {}: `{}`",
+                day, line, column, reason, text
+            ),
+            Self::Parse {
+                day,
+                line,
+                column: None,
+                text,
+                reason,
+            } => write!(f, "day {}, line {}: {}: `{}`", day, line, reason, text),
+            Self::Unsolvable { day, reason } => write!(f, "day {}: {}", day, reason),
+            Self::Input {
+                day,
+                source,
+                reason,
+            } => write!(f, "day {}: could not read {}: {}", day, source, reason),
+            Self::Manifest { line, text, reason } => {
+                write!(f, "answers, line {}: {}: `{}`", line, reason, text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    #[test]
+    fn test_display_parse_error_with_column() {
+        let error = Error::parse(6, "x", "invalid timer")
+            .at_line(1)
+            .at_column(7);
+        let expected = "day 6, line 1, column 7: invalid timer: `x`";
+        let actual = error.to_string();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_display_parse_error_without_column() {
+        let error = Error::parse(2, "sideways 5", "unknown direction").at_line(4);
+        let expected = "day 2, line 4: unknown direction: `sideways 5`";
+        let actual = error.to_string();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_display_manifest_error() {
+        let error = Error::manifest(3, "day_zero", "unknown day");
+        let expected = "answers, line 3: unknown day: `day_zero`";
+        let actual = error.to_string();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_column_of_token() {
+        let line = "22 13 17 11  0";
+        let token = line.split_whitespace().nth(4).unwrap();
+        let expected = 14;
+        let actual = super::column_of(line, token);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_at_column_offsets_existing_column() {
+        let error = Error::parse(4, "x", "invalid number")
+            .at_column(3)
+            .at_column(5);
+        let expected = Some(7);
+        let actual = match error {
+            Error::Parse { column, .. } => column,
+            _ => None,
+        };
+
+        assert_eq!(expected, actual);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{Day, Error, Result};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Where puzzle inputs are downloaded from unless another base URL is given.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2021";
+
+/// The environment variable holding the session token, which takes precedence over the file.
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+
+/// The file holding the session token when it is not set in the environment.
+pub const SESSION_FILE: &str = ".session";
+
+/// Returns the session token from the environment, or else from the file at `path`.
+pub fn session(path: impl AsRef<Path>) -> Option<String> {
+    env::var(SESSION_VARIABLE)
+        .ok()
+        .or_else(|| fs::read_to_string(path).ok())
+        .map(|session| session.trim().to_string())
+        .filter(|session| !session.is_empty())
+}
+
+/// Downloads puzzle inputs using a session token, caching each one on disk.
+#[derive(Clone, Debug)]
+pub struct Fetcher {
+    base_url: String,
+    session: String,
+}
+
+impl Fetcher {
+    pub fn new(session: impl Into<String>) -> Self {
+        Self {
+            base_url: String::from(DEFAULT_BASE_URL),
+            session: session.into(),
+        }
+    }
+
+    /// Downloads from `base_url` rather than the Advent of Code site.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Returns the URL the input for `day` is downloaded from.
+    pub fn url(&self, day: &Day) -> String {
+        format!("{}/day/{}/input", self.base_url, day.number)
+    }
+
+    /// Downloads the input for `day` into `directory`, named as [`crate::input::Source`] expects,
+    /// and returns its path. Nothing is downloaded if the input is already there.
+    pub fn fetch(&self, day: &Day, directory: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = directory.as_ref().join(format!("{}.txt", day.name));
+
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let url = self.url(day);
+        let input = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", "github.com/03k64/aoc_2021")
+            .call()
+            .map_err(|e| match e {
+                ureq::Error::Status(status, _) => {
+                    format!("server responded with status {}", status)
+                }
+                e => e.to_string(),
+            })
+            .and_then(|response| response.into_string().map_err(|e| e.to_string()))
+            .map_err(|reason| Error::input(day.number, &url, reason))?;
+
+        fs::create_dir_all(directory.as_ref())
+            .and_then(|_| fs::write(&path, input))
+            .map_err(|e| Error::input(day.number, path.display().to_string(), e.to_string()))?;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fetcher;
+    use crate::Error;
+    use std::{
+        env, fs,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        path::PathBuf,
+        thread::{self, JoinHandle},
+    };
+
+    fn use_temporary_directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("aoc_2021_fetch_{}", name));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    /// Serves a single request with the given status and body, returning the request's head.
+    fn use_server(status: &'static str, body: &'static str) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let head: Vec<String> = BufReader::new(stream.try_clone().unwrap())
+                .lines()
+                .map(|line| line.unwrap())
+                .take_while(|line| !line.is_empty())
+                .collect();
+
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+
+            head
+        });
+
+        (url, server)
+    }
+
+    #[test]
+    #[cfg(feature = "day_six")]
+    fn test_fetch_downloads_input() {
+        let (url, server) = use_server("200 OK", "3,4,3,1,2\n");
+        let directory = use_temporary_directory("downloads");
+        let day = crate::day(6).unwrap();
+
+        let path = Fetcher::new("abc123")
+            .with_base_url(url)
+            .fetch(day, &directory)
+            .unwrap();
+        let head = server.join().unwrap();
+
+        let expected = (
+            String::from("GET /day/6/input HTTP/1.1"),
+            true,
+            String::from("3,4,3,1,2\n"),
+        );
+        let actual = (
+            head[0].clone(),
+            head.iter()
+                .any(|line| line.eq_ignore_ascii_case("cookie: session=abc123")),
+            fs::read_to_string(path).unwrap(),
+        );
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_six")]
+    fn test_fetch_uses_cached_input() {
+        let directory = use_temporary_directory("cached");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("day_six.txt"), "3,4,3,1,2").unwrap();
+        let day = crate::day(6).unwrap();
+
+        // Nothing is listening here, so any attempt to download would fail.
+        let expected = Ok(directory.join("day_six.txt"));
+        let actual = Fetcher::new("abc123")
+            .with_base_url("http://127.0.0.1:9")
+            .fetch(day, &directory);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_six")]
+    fn test_fetch_with_error_status() {
+        let (url, server) = use_server("400 Bad Request", "Please log in.");
+        let directory = use_temporary_directory("error_status");
+        let day = crate::day(6).unwrap();
+
+        let expected = Err(Error::input(
+            6,
+            format!("{}/day/6/input", url),
+            "server responded with status 400",
+        ));
+        let actual = Fetcher::new("expired")
+            .with_base_url(url)
+            .fetch(day, &directory);
+        server.join().unwrap();
+
+        assert_eq!(expected, actual);
+        assert!(!directory.join("day_six.txt").exists());
+    }
+}
@@ -0,0 +1,19 @@
+//! Entry points for the fuzz targets in `fuzz/` into parsers that are private to their day. Each
+//! returns whatever the parser does, and none may panic.
+
+#[cfg(feature = "day_four")]
+use crate::day_four::BingoCard;
+#[cfg(feature = "day_eight")]
+use std::collections::BTreeSet;
+
+/// See `BingoCard::new`.
+#[cfg(feature = "day_four")]
+pub fn bingo_card(matrix: Vec<Vec<usize>>) -> Option<BingoCard> {
+    BingoCard::new(matrix)
+}
+
+/// See `day_eight::segments_to_sets`.
+#[cfg(feature = "day_eight")]
+pub fn segments_to_sets(segments: &str) -> Vec<BTreeSet<char>> {
+    crate::day_eight::segments_to_sets(segments)
+}
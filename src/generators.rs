@@ -0,0 +1,572 @@
+use std::ops::RangeInclusive;
+
+/// A small pseudo-random number generator (SplitMix64), so that the same seed generates the same
+/// input on every platform.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the given inclusive `range`.
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let span = (range.end() - range.start()) as u64 + 1;
+        range.start() + (self.next_u64() % span) as usize
+    }
+
+    /// Returns `true` once in every `n` calls, on average.
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.range(1..=n) == 1
+    }
+
+    /// Returns one of `items`, each as likely as any other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` is empty, as every generator chooses from a fixed, non-empty set.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        assert!(!items.is_empty(), "cannot choose from no items");
+
+        &items[self.range(0..=items.len() - 1)]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for ix in (1..items.len()).rev() {
+            items.swap(ix, self.range(0..=ix));
+        }
+    }
+}
+
+/// A generator of inputs for a day, given a source of randomness and how large the input should be.
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// The generator for every day whose feature is enabled, in day order.
+pub static GENERATORS: &[(usize, Generator)] = &[
+    #[cfg(feature = "day_one")]
+    (1, day_one),
+    #[cfg(feature = "day_two")]
+    (2, day_two),
+    #[cfg(feature = "day_three")]
+    (3, day_three),
+    #[cfg(feature = "day_four")]
+    (4, day_four),
+    #[cfg(feature = "day_five")]
+    (5, day_five),
+    #[cfg(feature = "day_six")]
+    (6, day_six),
+    #[cfg(feature = "day_seven")]
+    (7, day_seven),
+    #[cfg(feature = "day_eight")]
+    (8, day_eight),
+    #[cfg(feature = "day_nine")]
+    (9, day_nine),
+    #[cfg(feature = "day_ten")]
+    (10, day_ten),
+    #[cfg(feature = "day_eleven")]
+    (11, day_eleven),
+    #[cfg(feature = "day_twelve")]
+    (12, day_twelve),
+    #[cfg(feature = "day_thirteen")]
+    (13, day_thirteen),
+    #[cfg(feature = "day_fourteen")]
+    (14, day_fourteen),
+    #[cfg(feature = "day_fifteen")]
+    (15, day_fifteen),
+];
+
+/// Generates an input for the given `day` from `seed`, scaled by `size` as described by each day's
+/// generator. Returns `None` if there is no generator for the day.
+pub fn generate(day: usize, size: usize, seed: u64) -> Option<String> {
+    GENERATORS
+        .iter()
+        .find(|(number, _)| *number == day)
+        .map(|(_, generator)| generator(&mut Rng::new(seed), size))
+}
+
+fn lines(lines: impl IntoIterator<Item = String>) -> String {
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+fn digit_grid(rng: &mut Rng, height: usize, width: usize, digits: RangeInclusive<usize>) -> String {
+    lines((0..height).map(|_| {
+        (0..width)
+            .map(|_| char::from(b'0' + rng.range(digits.clone()) as u8))
+            .collect()
+    }))
+}
+
+/// `size` sonar sweep depths, mostly increasing.
+#[cfg(feature = "day_one")]
+pub fn day_one(rng: &mut Rng, size: usize) -> String {
+    let mut depth = rng.range(100..=200);
+
+    lines((0..size).map(|_| {
+        depth = (depth + rng.range(0..=40)).saturating_sub(10);
+        depth.to_string()
+    }))
+}
+
+/// `size` submarine commands, never rising above the surface.
+#[cfg(feature = "day_two")]
+pub fn day_two(rng: &mut Rng, size: usize) -> String {
+    let mut depth = 0;
+
+    lines((0..size).map(|_| {
+        let units = rng.range(1..=9);
+
+        match rng.range(0..=2) {
+            0 if depth >= units => {
+                depth -= units;
+                format!("up {}", units)
+            }
+            1 => {
+                depth += units;
+                format!("down {}", units)
+            }
+            _ => format!("forward {}", units),
+        }
+    }))
+}
+
+/// `size` distinct diagnostic numbers, wide enough to keep them distinct.
+#[cfg(feature = "day_three")]
+pub fn day_three(rng: &mut Rng, size: usize) -> String {
+    let width = (usize::BITS - (size * 4).leading_zeros()).clamp(5, 63) as usize;
+    let mut seen = std::collections::HashSet::with_capacity(size);
+
+    lines((0..size).map(|_| loop {
+        let diagnostic = rng.next_u64() >> (64 - width);
+
+        if seen.insert(diagnostic) {
+            break format!("{:0width$b}", diagnostic, width = width);
+        }
+    }))
+}
+
+/// `size` bingo cards, with every number on them eventually drawn.
+#[cfg(feature = "day_four")]
+pub fn day_four(rng: &mut Rng, size: usize) -> String {
+    let mut draws: Vec<usize> = (0..(size * 25).max(100)).collect();
+    rng.shuffle(&mut draws);
+
+    let width = (draws.len() - 1).to_string().len();
+    let cards = (0..size).map(|_| {
+        let mut numbers = draws.clone();
+        rng.shuffle(&mut numbers);
+
+        numbers[..25]
+            .chunks(5)
+            .map(|row| {
+                let row: Vec<String> = row
+                    .iter()
+                    .map(|number| format!("{:>width$}", number, width = width))
+                    .collect();
+                row.join(" ") + "\n"
+            })
+            .collect::<String>()
+    });
+
+    let draws: Vec<String> = draws.iter().map(ToString::to_string).collect();
+    let mut input = draws.join(",") + "\n";
+
+    for card in cards {
+        input.push('\n');
+        input.push_str(&card);
+    }
+
+    input
+}
+
+/// `size` horizontal, vertical and diagonal lines of vents on a 1000 by 1000 floor.
+#[cfg(feature = "day_five")]
+pub fn day_five(rng: &mut Rng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let (x1, y1) = (rng.range(0..=999), rng.range(0..=999));
+        let length = rng.range(1..=200);
+
+        let (x2, y2) = match rng.range(0..=2) {
+            0 => ((x1 + length).min(999), y1),
+            1 => (x1, (y1 + length).min(999)),
+            _ => {
+                let length = length.min(999 - x1).min(y1);
+                (x1 + length, y1 - length)
+            }
+        };
+
+        if rng.one_in(2) {
+            format!("{},{} -> {},{}", x1, y1, x2, y2)
+        } else {
+            format!("{},{} -> {},{}", x2, y2, x1, y1)
+        }
+    }))
+}
+
+/// `size` lanternfish with their timers.
+#[cfg(feature = "day_six")]
+pub fn day_six(rng: &mut Rng, size: usize) -> String {
+    let timers: Vec<String> = (0..size).map(|_| rng.range(1..=5).to_string()).collect();
+
+    timers.join(",") + "\n"
+}
+
+/// `size` crab submarines, spread out across twice as many positions.
+#[cfg(feature = "day_seven")]
+pub fn day_seven(rng: &mut Rng, size: usize) -> String {
+    let positions: Vec<String> = (0..size)
+        .map(|_| rng.range(0..=size * 2).to_string())
+        .collect();
+
+    positions.join(",") + "\n"
+}
+
+/// `size` entries, each with its own scrambled wiring of the display.
+#[cfg(feature = "day_eight")]
+pub fn day_eight(rng: &mut Rng, size: usize) -> String {
+    const DIGITS: [&str; 10] = [
+        "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+    ];
+
+    lines((0..size).map(|_| {
+        let mut wiring: Vec<char> = ('a'..='g').collect();
+        rng.shuffle(&mut wiring);
+
+        let scramble = |rng: &mut Rng, digit: &str| {
+            let mut segments: Vec<char> = digit
+                .bytes()
+                .map(|segment| wiring[usize::from(segment - b'a')])
+                .collect();
+            rng.shuffle(&mut segments);
+            segments.into_iter().collect::<String>()
+        };
+
+        let mut patterns: Vec<String> = DIGITS.iter().map(|digit| scramble(rng, digit)).collect();
+        let values: Vec<String> = (0..4)
+            .map(|_| {
+                let digit = DIGITS[rng.range(0..=9)];
+                scramble(rng, digit)
+            })
+            .collect();
+        rng.shuffle(&mut patterns);
+
+        format!("{} | {}", patterns.join(" "), values.join(" "))
+    }))
+}
+
+/// A `size` by `size` height map, with walls of nines making sure there are at least three
+/// basins. Sizes smaller than five are rounded up.
+#[cfg(feature = "day_nine")]
+pub fn day_nine(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(5);
+    let walls = [size / 3, size * 2 / 3];
+
+    lines((0..size).map(|_| {
+        (0..size)
+            .map(|x| {
+                if walls.contains(&x) || rng.one_in(4) {
+                    '9'
+                } else {
+                    char::from(b'0' + rng.range(0..=8) as u8)
+                }
+            })
+            .collect()
+    }))
+}
+
+/// `size` lines of chunks, each either corrupted or incomplete. Chunks are nested no more than
+/// twelve deep, so that completion scores cannot overflow.
+#[cfg(feature = "day_ten")]
+pub fn day_ten(rng: &mut Rng, size: usize) -> String {
+    const CHUNKS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+    lines((0..size).map(|ix| {
+        let corrupted = ix > 0 && rng.one_in(2);
+        let length = rng.range(20..=100);
+        let mut line = String::with_capacity(length + 1);
+        let mut open = vec![];
+
+        while line.len() < length {
+            if open.is_empty() || (open.len() < 12 && rng.one_in(2)) {
+                let (opening, closing) = *rng.choose(&CHUNKS);
+                line.push(opening);
+                open.push(closing);
+            } else {
+                line.extend(open.pop());
+            }
+        }
+
+        if corrupted {
+            let expected = open.pop().unwrap_or(')');
+            let illegal: Vec<char> = CHUNKS
+                .iter()
+                .map(|(_, closing)| *closing)
+                .filter(|closing| *closing != expected)
+                .collect();
+            line.push(*rng.choose(&illegal));
+        } else if open.is_empty() {
+            line.push('(');
+        }
+
+        line
+    }))
+}
+
+/// Returns whether every octopus in the `size` by `size` grid of `energy_levels` flashes in the
+/// same step within `steps` steps.
+#[cfg(feature = "day_eleven")]
+fn synchronises(mut energy_levels: Vec<u8>, size: usize, steps: usize) -> bool {
+    (0..steps).any(|_| {
+        let mut flashing = vec![];
+
+        for (ix, energy_level) in energy_levels.iter_mut().enumerate() {
+            *energy_level += 1;
+
+            if *energy_level == 10 {
+                flashing.push(ix);
+            }
+        }
+
+        let mut flashes = 0;
+
+        while let Some(ix) = flashing.pop() {
+            flashes += 1;
+
+            let (x, y) = (ix % size, ix / size);
+            for ny in y.saturating_sub(1)..=(y + 1).min(size - 1) {
+                for nx in x.saturating_sub(1)..=(x + 1).min(size - 1) {
+                    let neighbour = ny * size + nx;
+
+                    if neighbour != ix {
+                        energy_levels[neighbour] += 1;
+
+                        if energy_levels[neighbour] == 10 {
+                            flashing.push(neighbour);
+                        }
+                    }
+                }
+            }
+        }
+
+        for energy_level in energy_levels.iter_mut().filter(|e| **e > 9) {
+            *energy_level = 0;
+        }
+
+        flashes == size * size
+    })
+}
+
+/// A `size` by `size` grid of dumbo octopus energy levels, which is known to synchronise within a
+/// thousand steps. Energy levels are kept low, as grids spread across every level rarely
+/// synchronise at all once they are larger than ten by ten.
+#[cfg(feature = "day_eleven")]
+pub fn day_eleven(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+
+    loop {
+        let energy_levels: Vec<u8> = (0..size * size).map(|_| rng.range(0..=5) as u8).collect();
+
+        if synchronises(energy_levels.clone(), size, 1000) {
+            break lines(energy_levels.chunks(size).map(|row| {
+                row.iter()
+                    .map(|energy_level| char::from(b'0' + energy_level))
+                    .collect()
+            }));
+        }
+    }
+}
+
+/// Names the cave with the given index, using at least two letters so that no name is shared with
+/// the start or end.
+#[cfg(feature = "day_twelve")]
+fn cave_name(mut ix: usize, big: bool) -> String {
+    let base = if big { b'A' } else { b'a' };
+    let mut name = vec![base + (ix % 26) as u8];
+    ix /= 26;
+
+    while name.len() < 2 || ix > 0 {
+        name.push(base + (ix % 26) as u8);
+        ix /= 26;
+    }
+
+    name.into_iter().map(char::from).rev().collect()
+}
+
+/// A cave system of `size` small caves, split between branches from the start to the end of up to
+/// three caves each, some of them around a big cave. Branches only meet at the start and end, so
+/// the number of paths grows with `size` rather than exploding.
+#[cfg(feature = "day_twelve")]
+pub fn day_twelve(rng: &mut Rng, size: usize) -> String {
+    let mut links = vec![];
+    let mut small = (0..size.max(1)).map(|ix| cave_name(ix, false)).peekable();
+    let mut big = (0..).map(|ix| cave_name(ix, true));
+
+    while small.peek().is_some() {
+        let branch: Vec<String> = small.by_ref().take(rng.range(1..=3)).collect();
+        let route = [String::from("start")]
+            .into_iter()
+            .chain(branch.iter().cloned())
+            .chain([String::from("end")])
+            .collect::<Vec<_>>();
+
+        links.extend(route.windows(2).map(|w| (w[0].clone(), w[1].clone())));
+
+        if rng.one_in(2) {
+            let big = big.next().expect("There are always more big caves");
+            links.extend(branch.iter().map(|cave| (big.clone(), cave.clone())));
+        }
+    }
+
+    rng.shuffle(&mut links);
+
+    lines(links.into_iter().map(|(a, b)| {
+        if rng.one_in(2) {
+            format!("{}-{}", a, b)
+        } else {
+            format!("{}-{}", b, a)
+        }
+    }))
+}
+
+/// `size` dots on paper folded three times along each axis down to a 40 by 6 code.
+#[cfg(feature = "day_thirteen")]
+pub fn day_thirteen(rng: &mut Rng, size: usize) -> String {
+    // The folds in the order they are made, each halving the paper along a line down its middle.
+    let mut folds = vec![];
+    let (mut width, mut height) = (40, 6);
+
+    for _ in 0..3 {
+        folds.push(('y', height));
+        height = height * 2 + 1;
+        folds.push(('x', width));
+        width = width * 2 + 1;
+    }
+
+    folds.reverse();
+
+    let dots = (0..size).map(|_| {
+        let (mut x, mut y) = (rng.range(0..=39), rng.range(0..=5));
+
+        // Unfold the paper, moving each dot to either side of the fold.
+        for (axis, line) in folds.iter().rev() {
+            if rng.one_in(2) {
+                match axis {
+                    'x' => x = line * 2 - x,
+                    _ => y = line * 2 - y,
+                }
+            }
+        }
+
+        format!("{},{}", x, y)
+    });
+    let folds = folds
+        .iter()
+        .map(|(axis, line)| format!("fold along {}={}", axis, line));
+
+    lines(dots.chain([String::new()]).chain(folds))
+}
+
+/// A polymer template `size` elements long, with a rule for every pair of its elements.
+#[cfg(feature = "day_fourteen")]
+pub fn day_fourteen(rng: &mut Rng, size: usize) -> String {
+    let mut elements: Vec<char> = ('A'..='Z').collect();
+    rng.shuffle(&mut elements);
+    elements.truncate(rng.range(4..=10));
+
+    let template: String = (0..size.max(2)).map(|_| *rng.choose(&elements)).collect();
+    let rules = elements.iter().flat_map(|left| {
+        elements
+            .iter()
+            .map(|right| format!("{}{} -> {}", left, right, rng.choose(&elements)))
+            .collect::<Vec<_>>()
+    });
+
+    lines([template, String::new()].into_iter().chain(rules))
+}
+
+/// A `size` by `size` map of risk levels.
+#[cfg(feature = "day_fifteen")]
+pub fn day_fifteen(rng: &mut Rng, size: usize) -> String {
+    digit_grid(rng, size, size, 1..=9)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+    use crate::{Part, DAYS};
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let expected: Vec<u64> = {
+            let mut rng = Rng::new(42);
+            (0..4).map(|_| rng.next_u64()).collect()
+        };
+        let actual: Vec<u64> = {
+            let mut rng = Rng::new(42);
+            (0..4).map(|_| rng.next_u64()).collect()
+        };
+
+        assert_eq!(expected, actual);
+        assert_ne!(Rng::new(43).next_u64(), actual[0]);
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let expected: Vec<Option<String>> = DAYS
+            .iter()
+            .map(|day| super::generate(day.number, 10, 7))
+            .collect();
+        let actual: Vec<Option<String>> = DAYS
+            .iter()
+            .map(|day| super::generate(day.number, 10, 7))
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_generated_inputs_are_solvable() {
+        for seed in 0..5 {
+            for (number, generator) in super::GENERATORS {
+                let day = crate::day(*number).unwrap();
+                let input = generator(&mut Rng::new(seed), 12);
+
+                for part in [Part::One, Part::Two] {
+                    if let Err(error) = day.solve(&input, part) {
+                        panic!("seed {}: {}\n{}", seed, error, input);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generators_are_for_registered_days_in_order() {
+        let expected: Vec<usize> = DAYS
+            .iter()
+            .map(|day| day.number)
+            .filter(|day| super::GENERATORS.iter().any(|(number, _)| number == day))
+            .collect();
+        let actual: Vec<usize> = super::GENERATORS.iter().map(|(day, _)| *day).collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_generate_with_unknown_day() {
+        let actual = super::generate(26, 10, 0);
+
+        assert!(actual.is_none());
+    }
+}
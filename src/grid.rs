@@ -0,0 +1,645 @@
+use crate::{position::Position, Error, Result};
+use std::ops::{Index, IndexMut};
+
+/// A rectangular grid of cells, held row by row and indexed by [`Position`], with the origin at
+/// the top left.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(try_from = "Cells<T>")
+)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    height: usize,
+    width: usize,
+}
+
+/// The fields of a deserialised [`Grid`], before they are checked to make a rectangle.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct Cells<T> {
+    cells: Vec<T>,
+    height: usize,
+    width: usize,
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<Cells<T>> for Grid<T> {
+    type Error = String;
+
+    /// Accepts the cells only if there are exactly enough for `height` rows as long as `width`,
+    /// just as every row given to [`Grid::parse`] must be as long as the first. Neither may be
+    /// zero, as [`Grid::parse`] never makes an empty grid.
+    fn try_from(grid: Cells<T>) -> std::result::Result<Self, Self::Error> {
+        let Cells {
+            cells,
+            height,
+            width,
+        } = grid;
+
+        if height == 0 || width == 0 {
+            return Err(String::from("expected at least one row and column"));
+        }
+
+        if height.checked_mul(width) != Some(cells.len()) {
+            return Err(format!(
+                "expected {} rows of {} cells, found {} cells",
+                height,
+                width,
+                cells.len()
+            ));
+        }
+
+        Ok(Self {
+            cells,
+            height,
+            width,
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid `height` rows tall and `width` columns wide, with every cell set to `value`.
+    pub fn new(height: usize, width: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; height * width],
+            height,
+            width,
+        }
+    }
+
+    /// Creates a grid from its `rows`, or returns `None` if they are not all as long as the first.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Option<Self> {
+        let mut cells = vec![];
+        let mut height = 0;
+        let mut width = None;
+
+        for row in rows {
+            if *width.get_or_insert(row.len()) != row.len() {
+                return None;
+            }
+
+            cells.extend(row);
+            height += 1;
+        }
+
+        Some(Self {
+            cells,
+            height,
+            width: width.unwrap_or_default(),
+        })
+    }
+
+    /// Parses a grid from a block of text, one row per line, turning each character into a cell
+    /// with `decode`. Every row must be as long as the first, and the error for one that is not
+    /// names the cells it expected with `cells_name`, as in "expected 10 heights". Errors from
+    /// `decode` are moved to the row and column of the character.
+    pub fn parse(
+        day: usize,
+        input: impl IntoIterator<Item = impl AsRef<str>>,
+        cells_name: &str,
+        mut decode: impl FnMut(char) -> Result<T>,
+    ) -> Result<Self> {
+        let mut cells = vec![];
+        let mut height = 0;
+        let mut width = None;
+
+        for (rix, line) in input.into_iter().enumerate() {
+            let line = line.as_ref();
+            let width = *width.get_or_insert(line.chars().count());
+
+            if line.chars().count() != width {
+                let reason = format!("expected {} {}", width, cells_name);
+                return Err(Error::parse(day, line, reason).at_line(rix + 1));
+            }
+
+            for (cix, c) in line.chars().enumerate() {
+                cells.push(decode(c).map_err(|e| e.at_line(rix + 1).at_column(cix + 1))?);
+            }
+
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            height,
+            width: width.unwrap_or_default(),
+        })
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the cell at `position`, or `None` if it is outside the grid.
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|ix| &self.cells[ix])
+    }
+
+    /// Returns the cell at `position` to be changed, or `None` if it is outside the grid.
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(move |ix| &mut self.cells[ix])
+    }
+
+    fn index_of(&self, position: Position) -> Option<usize> {
+        if position.x < self.width && position.y < self.height {
+            Some(position.y * self.width + position.x)
+        } else {
+            None
+        }
+    }
+
+    /// Returns every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        positions(self.height, self.width)
+    }
+
+    /// Returns every cell, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Returns every cell, row by row, to be changed.
+    pub fn cells_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Returns every cell along with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Returns each row in turn, from the top.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Returns each column in turn, from the left, as the cells in it from the top.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    /// Returns the positions in the grid next to `position`, including diagonally, or none if
+    /// `position` is outside the grid.
+    pub fn neighbours_all(&self, position: Position) -> impl Iterator<Item = Position> {
+        match self.index_of(position) {
+            Some(_) => position.neighbours_all(self.height, self.width),
+            None => vec![],
+        }
+        .into_iter()
+    }
+
+    /// Returns the positions in the grid directly above, below, left or right of `position`, or
+    /// none if `position` is outside the grid.
+    pub fn neighbours_orthogonal(&self, position: Position) -> impl Iterator<Item = Position> {
+        match self.index_of(position) {
+            Some(_) => position.neighbours_orthogonal(self.height, self.width),
+            None => vec![],
+        }
+        .into_iter()
+    }
+
+    /// Returns the positions next to `position`, including diagonally, treating the grid as if
+    /// its edges wrap around to the opposite side, or none if `position` is outside the grid.
+    pub fn neighbours_all_wrapping(&self, position: Position) -> impl Iterator<Item = Position> {
+        match self.index_of(position) {
+            Some(_) => position.neighbours_all_wrapping(self.height, self.width),
+            None => vec![],
+        }
+        .into_iter()
+    }
+
+    /// Returns the positions directly above, below, left or right of `position`, treating the
+    /// grid as if its edges wrap around to the opposite side, or none if `position` is outside
+    /// the grid.
+    pub fn neighbours_orthogonal_wrapping(
+        &self,
+        position: Position,
+    ) -> impl Iterator<Item = Position> {
+        match self.index_of(position) {
+            Some(_) => position.neighbours_orthogonal_wrapping(self.height, self.width),
+            None => vec![],
+        }
+        .into_iter()
+    }
+
+    /// Returns a grid of the same shape with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            height: self.height,
+            width: self.width,
+        }
+    }
+
+    /// Returns the grid with its rows made columns, so that the cell at `(x, y)` moves to `(y, x)`.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearrange(self.width, self.height, |Position { x, y }| Position {
+            x: y,
+            y: x,
+        })
+    }
+
+    /// Returns the grid mirrored left to right.
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearrange(self.height, self.width, |Position { x, y }| Position {
+            x: self.width - 1 - x,
+            y,
+        })
+    }
+
+    /// Returns the grid mirrored top to bottom.
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearrange(self.height, self.width, |Position { x, y }| Position {
+            x,
+            y: self.height - 1 - y,
+        })
+    }
+
+    /// Builds a grid of the given size whose cell at each position is taken from this grid's cell
+    /// at `source` of that position.
+    fn rearrange(&self, height: usize, width: usize, source: impl Fn(Position) -> Position) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: positions(height, width)
+                .map(|position| self[source(position)].clone())
+                .collect(),
+            height,
+            width,
+        }
+    }
+
+    /// Shrinks the grid to at most `height` rows and `width` columns, keeping its top left corner.
+    pub fn truncate(&mut self, height: usize, width: usize) {
+        let (height, width) = (height.min(self.height), width.min(self.width));
+
+        if width < self.width {
+            let old_width = self.width;
+            let mut ix = 0;
+            self.cells.retain(|_| {
+                ix += 1;
+                (ix - 1) % old_width < width
+            });
+        }
+
+        self.cells.truncate(height * width);
+        self.height = height;
+        self.width = width;
+    }
+}
+
+/// Returns a decoder for [`Grid::parse`] that reads each character as a decimal digit, raising an
+/// error with the given `reason` for any other character.
+pub fn digit<T: From<u8>>(day: usize, reason: &str) -> impl Fn(char) -> Result<T> + '_ {
+    move |c| {
+        c.to_digit(10)
+            .map(|digit| T::from(digit as u8))
+            .ok_or_else(|| Error::parse(day, c, reason))
+    }
+}
+
+fn positions(height: usize, width: usize) -> impl Iterator<Item = Position> {
+    (0..height).flat_map(move |y| (0..width).map(move |x| Position { x, y }))
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        self.get(position).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside a grid {} tall and {} wide",
+                position, self.height, self.width
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        let (height, width) = (self.height, self.width);
+
+        self.get_mut(position).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside a grid {} tall and {} wide",
+                position, height, width
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::{position::Position, Error};
+
+    fn use_example_grid() -> Grid<u32> {
+        Grid::from_rows([vec![1, 2, 3], vec![4, 5, 6]]).unwrap()
+    }
+
+    fn rows_of(grid: &Grid<u32>) -> Vec<Vec<u32>> {
+        grid.rows().map(<[u32]>::to_vec).collect()
+    }
+
+    #[test]
+    fn test_from_rows_with_ragged_rows() {
+        let actual = Grid::from_rows([vec![1, 2, 3], vec![4, 5]]);
+
+        assert!(actual.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise() {
+        let json = r#"{"cells": [1, 2, 3, 4, 5, 6], "height": 2, "width": 3}"#;
+        let expected = use_example_grid();
+        let actual: Grid<u32> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_with_missing_cells() {
+        let json = r#"{"cells": [1, 2, 3, 4, 5], "height": 2, "width": 3}"#;
+        let expected = "expected 2 rows of 3 cells, found 5 cells";
+        let actual = serde_json::from_str::<Grid<u32>>(json).unwrap_err();
+
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialise_empty() {
+        let json = r#"{"cells": [], "height": 0, "width": 3}"#;
+        let expected = "expected at least one row and column";
+        let actual = serde_json::from_str::<Grid<u32>>(json).unwrap_err();
+
+        assert_eq!(expected, actual.to_string());
+    }
+
+    #[test]
+    fn test_parse() {
+        let expected = Ok(use_example_grid());
+        let actual = Grid::parse(
+            0,
+            ["123", "456"],
+            "digits",
+            super::digit(0, "invalid digit"),
+        );
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_with_invalid_cell() {
+        let expected = Err(Error::parse(0, 'x', "invalid digit")
+            .at_line(2)
+            .at_column(3));
+        let actual = Grid::<u32>::parse(
+            0,
+            ["123", "45x"],
+            "digits",
+            super::digit(0, "invalid digit"),
+        );
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_parse_with_ragged_rows() {
+        let expected = Err(Error::parse(0, "4567", "expected 3 digits").at_line(2));
+        let actual = Grid::<u32>::parse(
+            0,
+            ["123", "4567"],
+            "digits",
+            super::digit(0, "invalid digit"),
+        );
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_dimensions() {
+        let grid = use_example_grid();
+        let expected = (2, 3);
+        let actual = (grid.height(), grid.width());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_index_by_position() {
+        let grid = use_example_grid();
+        let expected = 6;
+        let actual = grid[Position { x: 2, y: 1 }];
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_get_outside_the_grid() {
+        let grid = use_example_grid();
+        let actual = grid.get(Position { x: 3, y: 0 });
+
+        assert!(actual.is_none());
+    }
+
+    #[test]
+    fn test_index_mut_by_position() {
+        let mut grid = use_example_grid();
+        grid[Position { x: 0, y: 1 }] = 7;
+
+        let expected = vec![vec![1, 2, 3], vec![7, 5, 6]];
+        let actual = rows_of(&grid);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_columns() {
+        let expected = vec![vec![1, 4], vec![2, 5], vec![3, 6]];
+        let actual: Vec<Vec<u32>> = use_example_grid()
+            .columns()
+            .map(|column| column.copied().collect())
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_all() {
+        let grid = use_example_grid();
+        let expected = vec![
+            Position { x: 0, y: 1 },
+            Position { x: 2, y: 1 },
+            Position { x: 1, y: 1 },
+            Position { x: 0, y: 0 },
+            Position { x: 2, y: 0 },
+        ];
+        let actual: Vec<Position> = grid.neighbours_all(Position { x: 1, y: 0 }).collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_orthogonal() {
+        let grid = use_example_grid();
+        let expected = vec![Position { x: 1, y: 1 }, Position { x: 0, y: 0 }];
+        let actual: Vec<Position> = grid
+            .neighbours_orthogonal(Position { x: 0, y: 1 })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_outside_the_grid() {
+        let grid = use_example_grid();
+        let actual = grid.neighbours_all(Position { x: 3, y: 0 }).next();
+
+        assert!(actual.is_none());
+    }
+
+    #[test]
+    fn test_neighbours_all_wrapping_at_a_corner() {
+        let grid = Grid::new(3, 4, 0);
+        let expected = vec![
+            Position { x: 2, y: 0 },
+            Position { x: 0, y: 0 },
+            Position { x: 3, y: 0 },
+            Position { x: 2, y: 2 },
+            Position { x: 0, y: 2 },
+            Position { x: 2, y: 1 },
+            Position { x: 0, y: 1 },
+            Position { x: 3, y: 1 },
+        ];
+        let actual: Vec<Position> = grid
+            .neighbours_all_wrapping(Position { x: 3, y: 2 })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_all_wrapping_on_an_edge() {
+        let grid = Grid::new(3, 4, 0);
+        let expected = vec![
+            Position { x: 3, y: 2 },
+            Position { x: 1, y: 2 },
+            Position { x: 0, y: 2 },
+            Position { x: 3, y: 1 },
+            Position { x: 1, y: 1 },
+            Position { x: 3, y: 0 },
+            Position { x: 1, y: 0 },
+            Position { x: 0, y: 0 },
+        ];
+        let actual: Vec<Position> = grid
+            .neighbours_all_wrapping(Position { x: 0, y: 1 })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_orthogonal_wrapping_at_a_corner() {
+        let grid = Grid::new(3, 4, 0);
+        let expected = vec![
+            Position { x: 3, y: 0 },
+            Position { x: 2, y: 2 },
+            Position { x: 0, y: 2 },
+            Position { x: 3, y: 1 },
+        ];
+        let actual: Vec<Position> = grid
+            .neighbours_orthogonal_wrapping(Position { x: 3, y: 2 })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_orthogonal_wrapping_on_an_edge() {
+        let grid = Grid::new(3, 4, 0);
+        let expected = vec![
+            Position { x: 0, y: 2 },
+            Position { x: 3, y: 1 },
+            Position { x: 1, y: 1 },
+            Position { x: 0, y: 0 },
+        ];
+        let actual: Vec<Position> = grid
+            .neighbours_orthogonal_wrapping(Position { x: 0, y: 1 })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_wrapping_outside_the_grid() {
+        let grid = Grid::new(3, 4, 0);
+        let actual = grid.neighbours_all_wrapping(Position { x: 4, y: 0 }).next();
+
+        assert!(actual.is_none());
+    }
+
+    #[test]
+    fn test_transpose() {
+        let expected = vec![vec![1, 4], vec![2, 5], vec![3, 6]];
+        let actual = rows_of(&use_example_grid().transpose());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_flip_horizontal() {
+        let expected = vec![vec![3, 2, 1], vec![6, 5, 4]];
+        let actual = rows_of(&use_example_grid().flip_horizontal());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_flip_vertical() {
+        let expected = vec![vec![4, 5, 6], vec![1, 2, 3]];
+        let actual = rows_of(&use_example_grid().flip_vertical());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_map() {
+        let expected = vec![vec![2, 4, 6], vec![8, 10, 12]];
+        let actual = rows_of(&use_example_grid().map(|cell| cell * 2));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut grid = use_example_grid();
+        grid.truncate(5, 2);
+
+        let expected = vec![vec![1, 2], vec![4, 5]];
+        let actual = rows_of(&grid);
+
+        assert_eq!(expected, actual);
+    }
+}
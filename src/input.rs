@@ -0,0 +1,295 @@
+use crate::{Day, Error, Part, Result};
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+/// The directory holding one subdirectory of puzzle inputs per profile.
+pub const DEFAULT_DIRECTORY: &str = "input";
+
+/// The profile puzzle inputs are read from unless another source is given.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Where a day's raw puzzle input is loaded from at runtime.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Source {
+    /// A directory holding one `day_x.txt` file per day, named after the registered day.
+    Directory(PathBuf),
+    /// A single file holding the input for whichever day is being solved.
+    File(PathBuf),
+    /// Standard input, read to the end.
+    Stdin,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self::profile(DEFAULT_PROFILE)
+    }
+}
+
+impl From<&str> for Source {
+    /// Interprets a command line argument, where `-` means standard input and an existing
+    /// directory means a directory of inputs. Anything else is taken to be a file.
+    fn from(path: &str) -> Self {
+        let path = PathBuf::from(path);
+
+        if path.as_os_str() == "-" {
+            Self::Stdin
+        } else if path.is_dir() {
+            Self::Directory(path)
+        } else {
+            Self::File(path)
+        }
+    }
+}
+
+impl Source {
+    /// Returns the directory of inputs for the named profile, such as one person's puzzle inputs.
+    pub fn profile(name: &str) -> Self {
+        Self::Directory(Path::new(DEFAULT_DIRECTORY).join(name))
+    }
+
+    /// Returns the path the input for `day` would be read from, or `None` for standard input.
+    pub fn path(&self, day: &Day) -> Option<PathBuf> {
+        match self {
+            Self::Directory(directory) => Some(directory.join(format!("{}.txt", day.name))),
+            Self::File(path) => Some(path.clone()),
+            Self::Stdin => None,
+        }
+    }
+
+    /// Describes where the input for `day` is read from, for reporting errors reading it.
+    fn describe(&self, day: &Day) -> String {
+        self.path(day).map_or_else(
+            || String::from("standard input"),
+            |path| path.display().to_string(),
+        )
+    }
+
+    /// Reads the raw puzzle input for `day`, ready to be handed to [`Day::solve`].
+    pub fn load(&self, day: &Day) -> Result<String> {
+        let mut input = String::new();
+        self.open(day)?
+            .read_to_string(&mut input)
+            .map_err(|e| Error::input(day.number, self.describe(day), e.to_string()))?;
+
+        Ok(input)
+    }
+
+    /// Opens the raw puzzle input for `day` to be read a line at a time.
+    pub fn open(&self, day: &Day) -> Result<Box<dyn BufRead>> {
+        match self.path(day) {
+            Some(path) => File::open(&path)
+                .map(|file| Box::new(BufReader::new(file)) as Box<dyn BufRead>)
+                .map_err(|e| Error::input(day.number, self.describe(day), e.to_string())),
+            None => Ok(Box::new(io::stdin().lock())),
+        }
+    }
+
+    /// Returns the displayed answer to `part` of `day`, reading its input a line at a time so that
+    /// days able to stream their input never hold all of it in memory.
+    pub fn solve(&self, day: &Day, part: Part) -> Result<String> {
+        let mut lines = Lines::new(self.open(day)?);
+        let answer = day.solve_lines(&mut lines, part);
+
+        lines.finish(day.number, &self.describe(day))?;
+        answer
+    }
+}
+
+/// The lines read from `reader`, which stop at the first error reading it. That error is kept
+/// rather than yielded so the lines can be handed to a solver as they are, and must be checked for
+/// with [`Lines::finish`] once the solver is done.
+pub struct Lines<R> {
+    lines: io::Lines<R>,
+    error: Option<io::Error>,
+}
+
+impl<R> Lines<R>
+where
+    R: BufRead,
+{
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            error: None,
+        }
+    }
+
+    /// Returns the error that stopped the lines being read from `source`, if there was one.
+    pub fn finish(self, day: usize, source: &str) -> Result<()> {
+        self.error
+            .map_or(Ok(()), |e| Err(Error::input(day, source, e.to_string())))
+    }
+}
+
+impl<R> Iterator for Lines<R>
+where
+    R: BufRead,
+{
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error.is_some() {
+            return None;
+        }
+
+        match self.lines.next()? {
+            Ok(line) => Some(line),
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+/// Returns the name of every profile in `directory`, that is each subdirectory, in name order.
+pub fn profiles(directory: impl AsRef<Path>) -> io::Result<Vec<String>> {
+    let mut profiles = Vec::new();
+
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+
+        if entry.file_type()?.is_dir() {
+            profiles.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+
+    profiles.sort();
+
+    Ok(profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Source;
+    use std::{env, fs, path::PathBuf};
+
+    fn use_temporary_directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("aoc_2021_input_{}", name));
+        fs::create_dir_all(&directory).expect("Could not create temporary directory");
+        directory
+    }
+
+    #[test]
+    #[cfg(feature = "day_six")]
+    fn test_load_from_default_directory() {
+        let day = crate::day(6).unwrap();
+        let input = Source::default().load(day).unwrap();
+        let expected = Ok(String::from("390011"));
+        let actual = day.solve(&input, crate::Part::One);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_seven")]
+    fn test_load_from_directory() {
+        let directory = use_temporary_directory("directory");
+        fs::write(directory.join("day_seven.txt"), "16,1,2,0,4,2,7,1,2,14\n").unwrap();
+
+        let day = crate::day(7).unwrap();
+        let expected = Ok(String::from("16,1,2,0,4,2,7,1,2,14\n"));
+        let actual = Source::Directory(directory).load(day);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_seven")]
+    fn test_load_from_file() {
+        let directory = use_temporary_directory("file");
+        let path = directory.join("crabs.txt");
+        fs::write(&path, "16,1,2,0,4,2,7,1,2,14").unwrap();
+
+        let day = crate::day(7).unwrap();
+        let expected = Ok(String::from("16,1,2,0,4,2,7,1,2,14"));
+        let actual = Source::from(path.to_str().unwrap()).load(day);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_eight")]
+    fn test_load_from_missing_file() {
+        let directory = use_temporary_directory("missing");
+        let path = directory.join("day_eight.txt");
+
+        let day = crate::day(8).unwrap();
+        let actual = Source::File(path.clone()).load(day);
+
+        match actual {
+            Err(crate::Error::Input { day, source, .. }) => {
+                assert_eq!(8, day);
+                assert_eq!(path.display().to_string(), source);
+            }
+            _ => panic!("Expected an input error, found {:?}", actual),
+        }
+    }
+
+    #[test]
+    fn test_profiles() {
+        let directory = use_temporary_directory("profiles");
+        fs::create_dir_all(directory.join("bob")).unwrap();
+        fs::create_dir_all(directory.join("alice")).unwrap();
+        fs::write(directory.join("README.txt"), "Not a profile").unwrap();
+
+        let expected = vec![String::from("alice"), String::from("bob")];
+        let actual = super::profiles(&directory).unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_seven")]
+    fn test_path_for_profile() {
+        let day = crate::day(7).unwrap();
+        let expected = Some(PathBuf::from("input/example/day_seven.txt"));
+        let actual = Source::profile("example").path(day);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_one")]
+    fn test_solve_streams_from_file() {
+        let directory = use_temporary_directory("solve");
+        let path = directory.join("sonar.txt");
+        fs::write(&path, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n").unwrap();
+
+        let day = crate::day(1).unwrap();
+        let expected = Ok(String::from("5"));
+        let actual = Source::File(path).solve(day, crate::Part::Two);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_one")]
+    fn test_solve_with_unreadable_line() {
+        let directory = use_temporary_directory("unreadable");
+        let path = directory.join("sonar.txt");
+        fs::write(&path, b"199\n200\n\xff\n").unwrap();
+
+        let day = crate::day(1).unwrap();
+        let actual = Source::File(path.clone()).solve(day, crate::Part::One);
+
+        match actual {
+            Err(crate::Error::Input { day, source, .. }) => {
+                assert_eq!(1, day);
+                assert_eq!(path.display().to_string(), source);
+            }
+            _ => panic!("Expected an input error, found {:?}", actual),
+        }
+    }
+
+    #[test]
+    fn test_source_from_dash_is_stdin() {
+        let expected = Source::Stdin;
+        let actual = Source::from("-");
+
+        assert_eq!(expected, actual);
+    }
+}
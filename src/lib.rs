@@ -1,18 +1,481 @@
 #![allow(dead_code)]
 
-mod day_eight;
+use std::{
+    any::Any,
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+pub use error::{Error, Result};
+
+pub mod allocations;
+pub mod answers;
+#[cfg(feature = "day_eight")]
+pub mod day_eight;
+#[cfg(feature = "day_eleven")]
 pub mod day_eleven;
-mod day_fifteen;
-mod day_five;
-mod day_four;
-mod day_fourteen;
-mod day_nine;
-mod day_one;
-mod day_seven;
-mod day_six;
-mod day_ten;
-mod day_thirteen;
-mod day_three;
-mod day_twelve;
-mod day_two;
-mod position;
+#[cfg(feature = "day_fifteen")]
+pub mod day_fifteen;
+#[cfg(feature = "day_five")]
+pub mod day_five;
+#[cfg(feature = "day_four")]
+pub mod day_four;
+#[cfg(feature = "day_fourteen")]
+pub mod day_fourteen;
+#[cfg(feature = "day_nine")]
+pub mod day_nine;
+#[cfg(feature = "day_one")]
+pub mod day_one;
+#[cfg(feature = "day_seven")]
+pub mod day_seven;
+#[cfg(feature = "day_six")]
+pub mod day_six;
+#[cfg(feature = "day_ten")]
+pub mod day_ten;
+#[cfg(feature = "day_thirteen")]
+pub mod day_thirteen;
+#[cfg(feature = "day_three")]
+pub mod day_three;
+#[cfg(feature = "day_twelve")]
+pub mod day_twelve;
+#[cfg(feature = "day_two")]
+pub mod day_two;
+mod error;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing;
+pub mod generators;
+pub mod grid;
+pub mod input;
+pub mod observer;
+pub mod point;
+pub mod position;
+pub mod repl;
+pub mod report;
+pub mod scaffold;
+
+/// A solution to both parts of a single day's puzzle. The raw puzzle input is parsed once into
+/// `Input`, which is then shared by both parts.
+pub trait Solution {
+    const DAY: usize;
+
+    type Input;
+    type PartOne: Display;
+    type PartTwo: Display;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne>;
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo>;
+
+    /// Names the parameter that sets the two parts apart, such as how many steps a simulation runs
+    /// for, on days whose puzzle can be solved for any value of it.
+    const PARAMETER: Option<&'static str> = None;
+
+    /// Solves the puzzle with [`Solution::PARAMETER`] set to `value`.
+    fn solve_with(_input: &Self::Input, _value: usize) -> Result<String>
+    where
+        Self: Sized,
+    {
+        Err(Error::unsolvable(
+            Self::DAY,
+            "there is no parameter to change",
+        ))
+    }
+
+    /// Solves `part` from the puzzle input given a line at a time. Days that can solve a part
+    /// without holding all of their input in memory override this, while by default the lines are
+    /// collected and parsed as usual.
+    fn solve_lines(lines: &mut dyn Iterator<Item = String>, part: Part) -> Result<String>
+    where
+        Self: Sized,
+    {
+        solve_collected::<Self>(lines, part)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "1" | "one" => Ok(Self::One),
+            "2" | "two" => Ok(Self::Two),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A registered day, allowing its [`Solution`] to be called without knowing its concrete type.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: usize,
+    pub name: &'static str,
+    /// See [`Solution::PARAMETER`].
+    pub parameter: Option<&'static str>,
+    solve: fn(&str, Part) -> Result<String>,
+    parse: fn(&str) -> Result<Model>,
+    solve_model: fn(&Model, Part) -> Result<String>,
+    solve_with: fn(&Model, usize) -> Result<String>,
+    solve_lines: fn(&mut dyn Iterator<Item = String>, Part) -> Result<String>,
+    run: fn(&str) -> Result<Run>,
+}
+
+/// A day's puzzle input once parsed, kept so that it can be solved again and again without being
+/// parsed each time. See [`Day::parse`].
+pub struct Model {
+    day: usize,
+    input: Box<dyn Any + Send + Sync>,
+}
+
+/// The answers to both parts of a day, and how long it took to parse the input and solve each part.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Run {
+    pub parse_time: Duration,
+    pub part_one: Result<Answer>,
+    pub part_two: Result<Answer>,
+}
+
+impl Run {
+    pub fn part(&self, part: Part) -> &Result<Answer> {
+        match part {
+            Part::One => &self.part_one,
+            Part::Two => &self.part_two,
+        }
+    }
+}
+
+/// The displayed answer to a single part, and how long it took to solve.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Answer {
+    pub value: String,
+    pub solve_time: Duration,
+}
+
+impl Day {
+    const fn new<S>(name: &'static str) -> Self
+    where
+        S: Solution,
+        S::Input: Send + Sync + 'static,
+    {
+        Self {
+            number: S::DAY,
+            name,
+            parameter: S::PARAMETER,
+            solve: solve::<S>,
+            parse: parse_model::<S>,
+            solve_model: solve_model::<S>,
+            solve_with: solve_model_with::<S>,
+            solve_lines: S::solve_lines,
+            run: run::<S>,
+        }
+    }
+
+    /// Parses the raw puzzle `input` and returns the displayed answer to the given `part`.
+    pub fn solve(&self, input: &str, part: Part) -> Result<String> {
+        (self.solve)(input, part)
+    }
+
+    /// Parses the raw puzzle `input` into a model that can be solved any number of times.
+    pub fn parse(&self, input: &str) -> Result<Model> {
+        (self.parse)(input)
+    }
+
+    /// Returns the displayed answer to the given `part` from a `model` this day has parsed.
+    pub fn solve_model(&self, model: &Model, part: Part) -> Result<String> {
+        (self.solve_model)(model, part)
+    }
+
+    /// Returns the displayed answer from a `model` this day has parsed, with its
+    /// [`Day::parameter`] set to `value`.
+    pub fn solve_with(&self, model: &Model, value: usize) -> Result<String> {
+        (self.solve_with)(model, value)
+    }
+
+    /// Returns the displayed answer to the given `part`, reading the puzzle input a line at a time
+    /// from `lines`. See [`Solution::solve_lines`].
+    pub fn solve_lines(
+        &self,
+        lines: &mut dyn Iterator<Item = String>,
+        part: Part,
+    ) -> Result<String> {
+        (self.solve_lines)(lines, part)
+    }
+
+    /// Parses the raw puzzle `input` once and solves both parts, timing each step. Only a failure
+    /// to parse is returned as an error, as each part may fail independently of the other.
+    pub fn run(&self, input: &str) -> Result<Run> {
+        (self.run)(input)
+    }
+}
+
+fn solve<S>(input: &str, part: Part) -> Result<String>
+where
+    S: Solution,
+{
+    answer::<S>(&S::parse(input)?, part)
+}
+
+fn answer<S>(input: &S::Input, part: Part) -> Result<String>
+where
+    S: Solution,
+{
+    match part {
+        Part::One => S::part_one(input).map(|answer| answer.to_string()),
+        Part::Two => S::part_two(input).map(|answer| answer.to_string()),
+    }
+}
+
+fn parse_model<S>(input: &str) -> Result<Model>
+where
+    S: Solution,
+    S::Input: Send + Sync + 'static,
+{
+    Ok(Model {
+        day: S::DAY,
+        input: Box::new(S::parse(input)?),
+    })
+}
+
+fn input_of<S>(model: &Model) -> Result<&S::Input>
+where
+    S: Solution,
+    S::Input: 'static,
+{
+    model
+        .input
+        .downcast_ref()
+        .filter(|_| model.day == S::DAY)
+        .ok_or_else(|| Error::unsolvable(S::DAY, format!("model was parsed by day {}", model.day)))
+}
+
+fn solve_model<S>(model: &Model, part: Part) -> Result<String>
+where
+    S: Solution,
+    S::Input: 'static,
+{
+    answer::<S>(input_of::<S>(model)?, part)
+}
+
+fn solve_model_with<S>(model: &Model, value: usize) -> Result<String>
+where
+    S: Solution,
+    S::Input: 'static,
+{
+    S::solve_with(input_of::<S>(model)?, value)
+}
+
+/// Solves `part` by collecting every line into the raw puzzle input and parsing it.
+pub(crate) fn solve_collected<S>(
+    lines: &mut dyn Iterator<Item = String>,
+    part: Part,
+) -> Result<String>
+where
+    S: Solution,
+{
+    let input: String = lines.flat_map(|line| [line, String::from("\n")]).collect();
+
+    solve::<S>(&input, part)
+}
+
+/// Hands the values in `results` to `f` until the first error, which is then returned in place of
+/// whatever `f` returned.
+pub(crate) fn process_results<T, U>(
+    results: impl IntoIterator<Item = Result<T>>,
+    f: impl FnOnce(&mut dyn Iterator<Item = T>) -> U,
+) -> Result<U> {
+    let mut error = None;
+    let output = {
+        let mut values = results
+            .into_iter()
+            .map_while(|result| result.map_err(|e| error = Some(e)).ok());
+        f(&mut values)
+    };
+
+    error.map_or(Ok(output), Err)
+}
+
+fn run<S>(input: &str) -> Result<Run>
+where
+    S: Solution,
+{
+    let start = Instant::now();
+    let input = S::parse(input)?;
+    let parse_time = start.elapsed();
+
+    Ok(Run {
+        parse_time,
+        part_one: time(|| S::part_one(&input)),
+        part_two: time(|| S::part_two(&input)),
+    })
+}
+
+pub(crate) fn time<T>(solve: impl FnOnce() -> Result<T>) -> Result<Answer>
+where
+    T: Display,
+{
+    let start = Instant::now();
+    let value = solve()?;
+    let solve_time = start.elapsed();
+
+    Ok(Answer {
+        value: value.to_string(),
+        solve_time,
+    })
+}
+
+/// Every solved day whose feature is enabled, in day order.
+pub static DAYS: &[Day] = &[
+    #[cfg(feature = "day_one")]
+    Day::new::<day_one::DayOne>("day_one"),
+    #[cfg(feature = "day_two")]
+    Day::new::<day_two::DayTwo>("day_two"),
+    #[cfg(feature = "day_three")]
+    Day::new::<day_three::DayThree>("day_three"),
+    #[cfg(feature = "day_four")]
+    Day::new::<day_four::DayFour>("day_four"),
+    #[cfg(feature = "day_five")]
+    Day::new::<day_five::DayFive>("day_five"),
+    #[cfg(feature = "day_six")]
+    Day::new::<day_six::DaySix>("day_six"),
+    #[cfg(feature = "day_seven")]
+    Day::new::<day_seven::DaySeven>("day_seven"),
+    #[cfg(feature = "day_eight")]
+    Day::new::<day_eight::DayEight>("day_eight"),
+    #[cfg(feature = "day_nine")]
+    Day::new::<day_nine::DayNine>("day_nine"),
+    #[cfg(feature = "day_ten")]
+    Day::new::<day_ten::DayTen>("day_ten"),
+    #[cfg(feature = "day_eleven")]
+    Day::new::<day_eleven::DayEleven>("day_eleven"),
+    #[cfg(feature = "day_twelve")]
+    Day::new::<day_twelve::DayTwelve>("day_twelve"),
+    #[cfg(feature = "day_thirteen")]
+    Day::new::<day_thirteen::DayThirteen>("day_thirteen"),
+    #[cfg(feature = "day_fourteen")]
+    Day::new::<day_fourteen::DayFourteen>("day_fourteen"),
+    #[cfg(feature = "day_fifteen")]
+    Day::new::<day_fifteen::DayFifteen>("day_fifteen"),
+];
+
+/// Returns the registered day with the given `number`, if it has been solved.
+pub fn day(number: usize) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{input::Source, Error, Part, Result, DAYS};
+
+    #[test]
+    fn test_days_are_registered_in_order() {
+        let mut expected: Vec<usize> = super::DAYS.iter().map(|day| day.number).collect();
+        expected.sort_unstable();
+        expected.dedup();
+        let actual: Vec<usize> = super::DAYS.iter().map(|day| day.number).collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_one")]
+    fn test_solve_with_example_input() {
+        let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+        let expected = Some(Ok(String::from("5")));
+        let actual = super::day(1).map(|day| day.solve(input, Part::Two));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_one")]
+    fn test_run_with_example_input() {
+        let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263";
+        let expected = (Ok(String::from("7")), Ok(String::from("5")));
+        let actual = super::day(1)
+            .unwrap()
+            .run(input)
+            .map(|run| {
+                (
+                    run.part_one.map(|answer| answer.value),
+                    run.part_two.map(|answer| answer.value),
+                )
+            })
+            .unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_two")]
+    fn test_run_with_invalid_input() {
+        let input = "forward 5\nup x";
+        let expected = Err(Error::parse(2, "x", "invalid units")
+            .at_line(2)
+            .at_column(4));
+        let actual = super::day(2).unwrap().run(input);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(all(feature = "day_thirteen", feature = "day_fourteen"))]
+    fn test_solve_model_parsed_by_another_day() {
+        let model = super::day(13)
+            .unwrap()
+            .parse("6,10\n\nfold along y=7")
+            .unwrap();
+        let expected = Err(Error::unsolvable(14, "model was parsed by day 13"));
+        let actual = super::day(14).unwrap().solve_model(&model, Part::One);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_solve_lines_agrees_with_solve() {
+        let source = Source::profile("example");
+        let expected: Vec<Result<String>> = DAYS
+            .iter()
+            .flat_map(|day| {
+                let input = source.load(day).unwrap();
+                [Part::One, Part::Two].map(|part| day.solve(&input, part))
+            })
+            .collect();
+        let actual: Vec<Result<String>> = DAYS
+            .iter()
+            .flat_map(|day| {
+                let input = source.load(day).unwrap();
+                [Part::One, Part::Two]
+                    .map(|part| day.solve_lines(&mut input.lines().map(String::from), part))
+            })
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_process_results_stops_at_first_error() {
+        let results = vec![Ok(1), Ok(2), Err(Error::unsolvable(1, "no")), Ok(4)];
+        let expected = Err(Error::unsolvable(1, "no"));
+        let actual = super::process_results(results, |values| values.sum::<usize>());
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_day_with_unknown_day() {
+        let actual = super::day(26);
+
+        assert!(actual.is_none());
+    }
+}
@@ -0,0 +1,17 @@
+/// Watches a simulation run, being shown its state after every step so that it can be logged,
+/// drawn or checked without copying the simulation. Any closure taking the step and the state is
+/// an observer, while `()` ignores every step.
+pub trait Observer<S: ?Sized> {
+    /// Called once the given one-based `step` has finished, with the state it left behind.
+    fn observe(&mut self, step: usize, state: &S);
+}
+
+impl<S: ?Sized> Observer<S> for () {
+    fn observe(&mut self, _step: usize, _state: &S) {}
+}
+
+impl<S: ?Sized, F: FnMut(usize, &S)> Observer<S> for F {
+    fn observe(&mut self, step: usize, state: &S) {
+        self(step, state)
+    }
+}
@@ -0,0 +1,342 @@
+use std::ops::{Add, Neg, Sub};
+
+/// A point in `N` dimensions without bounds, such as `Point([x, y, z])` in three.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point<const N: usize>(pub [isize; N]);
+
+impl<const N: usize> Point<N> {
+    pub fn origin() -> Self {
+        Self([0; N])
+    }
+
+    /// Returns the number of steps along the axes needed to get from this point to `other`.
+    pub fn manhattan_distance(self, other: Self) -> usize {
+        self.0.iter().zip(other.0).map(|(a, b)| a.abs_diff(b)).sum()
+    }
+
+    /// Returns the `2 * N` points a single step away along one axis.
+    pub fn neighbours_orthogonal(self) -> impl Iterator<Item = Self> {
+        (0..N).flat_map(move |axis| {
+            [-1, 1].into_iter().map(move |step| {
+                let mut neighbour = self;
+                neighbour.0[axis] += step;
+                neighbour
+            })
+        })
+    }
+
+    /// Returns the `3^N - 1` points that touch this one, including diagonally.
+    pub fn neighbours_all(self) -> impl Iterator<Item = Self> {
+        // Each offset counts in base three, with each digit being the step along one axis.
+        let count = 3usize.pow(N as u32);
+
+        (0..count)
+            .filter(move |&offset| offset != count / 2)
+            .map(move |offset| {
+                let mut neighbour = self;
+                let mut digits = offset;
+
+                for coordinate in neighbour.0.iter_mut() {
+                    *coordinate += (digits % 3) as isize - 1;
+                    digits /= 3;
+                }
+
+                neighbour
+            })
+    }
+}
+
+impl Point<3> {
+    /// Returns the point turned to each of the 24 ways a cube can face, starting with the point
+    /// as it is. The rotations come in the same order for every point, so points rotated by the
+    /// same index stay in the same place relative to one another.
+    pub fn rotations(self) -> [Self; 24] {
+        let roll = |Point([x, y, z]): Self| Point([x, z, -y]);
+        let turn = |Point([x, y, z]): Self| Point([-y, x, z]);
+
+        let mut rotations = [self; 24];
+        let mut current = self;
+        let mut ix = 0;
+
+        // Rolling then turning three times, three times over, visits 12 orientations. Rolling,
+        // turning and rolling again then faces the other way to visit the remaining 12.
+        for _ in 0..2 {
+            for _ in 0..3 {
+                current = roll(current);
+                rotations[ix] = current;
+                ix += 1;
+
+                for _ in 0..3 {
+                    current = turn(current);
+                    rotations[ix] = current;
+                    ix += 1;
+                }
+            }
+
+            current = roll(turn(roll(current)));
+        }
+
+        // The first half of the walk ends on the point as it started, so move that to the front.
+        rotations.rotate_left(11);
+        rotations
+    }
+}
+
+impl<const N: usize> Add for Point<N> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self.0.iter_mut().zip(other.0).for_each(|(a, b)| *a += b);
+        self
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self + -other
+    }
+}
+
+impl<const N: usize> Neg for Point<N> {
+    type Output = Self;
+
+    fn neg(mut self) -> Self::Output {
+        self.0.iter_mut().for_each(|a| *a = -*a);
+        self
+    }
+}
+
+/// The smallest box, aligned with the axes, holding some points. Both corners are inside it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BoundingBox<const N: usize> {
+    pub min: Point<N>,
+    pub max: Point<N>,
+}
+
+impl<const N: usize> BoundingBox<N> {
+    /// Creates the box holding every one of `points`, or returns `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Point<N>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        Some(points.fold(Self::from_point(first), Self::expand))
+    }
+
+    fn from_point(point: Point<N>) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// Returns the box grown just enough to hold `point`.
+    pub fn expand(mut self, point: Point<N>) -> Self {
+        for axis in 0..N {
+            self.min.0[axis] = self.min.0[axis].min(point.0[axis]);
+            self.max.0[axis] = self.max.0[axis].max(point.0[axis]);
+        }
+
+        self
+    }
+
+    pub fn contains(&self, point: Point<N>) -> bool {
+        (0..N).all(|axis| (self.min.0[axis]..=self.max.0[axis]).contains(&point.0[axis]))
+    }
+
+    /// Returns the box covered by both this box and `other`, or `None` if they do not overlap.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut intersection = *self;
+
+        for axis in 0..N {
+            intersection.min.0[axis] = self.min.0[axis].max(other.min.0[axis]);
+            intersection.max.0[axis] = self.max.0[axis].min(other.max.0[axis]);
+
+            if intersection.min.0[axis] > intersection.max.0[axis] {
+                return None;
+            }
+        }
+
+        Some(intersection)
+    }
+
+    /// Returns how many points are held by the box, or `None` if there are too many to count.
+    pub fn volume(&self) -> Option<usize> {
+        (0..N).try_fold(1usize, |volume, axis| {
+            let length = self.max.0[axis].abs_diff(self.min.0[axis]).checked_add(1)?;
+            volume.checked_mul(length)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundingBox, Point};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_manhattan_distance() {
+        let expected = 3621;
+        let actual = Point([1105, -1205, 1229]).manhattan_distance(Point([-92, -2380, -20]));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_orthogonal() {
+        let expected = vec![Point([-1, 0]), Point([1, 0]), Point([0, -1]), Point([0, 1])];
+        let actual: Vec<Point<2>> = Point([0, 0]).neighbours_orthogonal().collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_all() {
+        let expected = vec![
+            Point([-1, -1]),
+            Point([0, -1]),
+            Point([1, -1]),
+            Point([-1, 0]),
+            Point([1, 0]),
+            Point([-1, 1]),
+            Point([0, 1]),
+            Point([1, 1]),
+        ];
+        let actual: Vec<Point<2>> = Point([0, 0]).neighbours_all().collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_all_in_four_dimensions() {
+        let expected = 80;
+        let actual = Point([0, 0, 0, 0])
+            .neighbours_all()
+            .collect::<HashSet<_>>()
+            .len();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_all_exclude_the_point() {
+        let point = Point([0, 0, 0, 0]);
+        let actual = point.neighbours_all().any(|neighbour| neighbour == point);
+
+        assert!(!actual);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let expected = vec![
+            Point([-3, -2, -1]),
+            Point([-3, -1, 2]),
+            Point([-3, 1, -2]),
+            Point([-3, 2, 1]),
+            Point([-2, -3, 1]),
+            Point([-2, -1, -3]),
+            Point([-2, 1, 3]),
+            Point([-2, 3, -1]),
+            Point([-1, -3, -2]),
+            Point([-1, -2, 3]),
+            Point([-1, 2, -3]),
+            Point([-1, 3, 2]),
+            Point([1, -3, 2]),
+            Point([1, -2, -3]),
+            Point([1, 2, 3]),
+            Point([1, 3, -2]),
+            Point([2, -3, -1]),
+            Point([2, -1, 3]),
+            Point([2, 1, -3]),
+            Point([2, 3, 1]),
+            Point([3, -2, 1]),
+            Point([3, -1, -2]),
+            Point([3, 1, 2]),
+            Point([3, 2, -1]),
+        ];
+        let mut actual = Point([1, 2, 3]).rotations().to_vec();
+        actual.sort();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_rotations_start_with_the_point() {
+        let expected = Point([1, 2, 3]);
+        let actual = Point([1, 2, 3]).rotations()[0];
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_rotations_keep_points_relative_to_one_another() {
+        let (a, b) = (Point([1, 2, 3]), Point([-4, 7, 5]));
+        let expected = (a - b).rotations();
+        let (a_rotations, b_rotations) = (a.rotations(), b.rotations());
+        let actual: Vec<Point<3>> = (0..24)
+            .map(|ix| a_rotations[ix] - b_rotations[ix])
+            .collect();
+
+        assert_eq!(expected.to_vec(), actual);
+    }
+
+    #[test]
+    fn test_bounding_box_volume() {
+        let bounding_box =
+            BoundingBox::from_points([Point([10, 10, 10]), Point([12, 12, 12])]).unwrap();
+        let expected = Some(27);
+        let actual = bounding_box.volume();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_bounding_box_volume_too_large_to_count() {
+        let bounding_box =
+            BoundingBox::from_points([Point([isize::MIN, 0]), Point([isize::MAX, 0])]).unwrap();
+        let actual = bounding_box.volume();
+
+        assert!(actual.is_none());
+    }
+
+    #[test]
+    fn test_bounding_box_contains_point_inside() {
+        let bounding_box =
+            BoundingBox::from_points([Point([10, 10, 10]), Point([12, 12, 12])]).unwrap();
+        let actual = bounding_box.contains(Point([11, 10, 12]));
+
+        assert!(actual);
+    }
+
+    #[test]
+    fn test_bounding_box_does_not_contain_point_outside() {
+        let bounding_box =
+            BoundingBox::from_points([Point([10, 10, 10]), Point([12, 12, 12])]).unwrap();
+        let actual = bounding_box.contains(Point([11, 13, 12]));
+
+        assert!(!actual);
+    }
+
+    #[test]
+    fn test_bounding_box_intersection() {
+        let a = BoundingBox::from_points([Point([10, 10, 10]), Point([12, 12, 12])]).unwrap();
+        let b = BoundingBox::from_points([Point([11, 11, 11]), Point([13, 13, 13])]).unwrap();
+        let expected = Some(BoundingBox {
+            min: Point([11, 11, 11]),
+            max: Point([12, 12, 12]),
+        });
+        let actual = a.intersection(&b);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_bounding_box_intersection_without_overlap() {
+        let a = BoundingBox::from_points([Point([10, 10, 10]), Point([12, 12, 12])]).unwrap();
+        let b = BoundingBox::from_points([Point([0, 0, 13])]).unwrap();
+        let actual = a.intersection(&b);
+
+        assert!(actual.is_none());
+    }
+}
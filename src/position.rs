@@ -1,4 +1,11 @@
+use std::{
+    convert::TryFrom,
+    num::TryFromIntError,
+    ops::{Add, Sub},
+};
+
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Position {
     pub x: usize,
     pub y: usize,
@@ -17,17 +24,65 @@ impl Position {
             self.up(),
         ]
         .into_iter()
-        .filter_map(|n| n)
+        .flatten()
         .collect()
     }
 
     pub fn neighbours_orthogonal(self, height: usize, width: usize) -> Vec<Self> {
         vec![self.down(height), self.left(), self.right(width), self.up()]
             .into_iter()
-            .filter_map(|n| n)
+            .flatten()
             .collect()
     }
 
+    /// Returns the eight positions next to this one, including diagonally, on a grid whose edges
+    /// wrap around to the opposite side. On grids fewer than three positions across, the same
+    /// position can be a neighbour more than once.
+    pub fn neighbours_all_wrapping(self, height: usize, width: usize) -> Vec<Self> {
+        [
+            (-1, 1),
+            (1, 1),
+            (0, 1),
+            (-1, 0),
+            (1, 0),
+            (-1, -1),
+            (1, -1),
+            (0, -1),
+        ]
+        .into_iter()
+        .map(|(dx, dy)| self.offset_wrapping(dx, dy, height, width))
+        .collect()
+    }
+
+    /// Returns the four positions directly above, below, left and right of this one on a grid
+    /// whose edges wrap around to the opposite side.
+    pub fn neighbours_orthogonal_wrapping(self, height: usize, width: usize) -> Vec<Self> {
+        [(0, 1), (-1, 0), (1, 0), (0, -1)]
+            .into_iter()
+            .map(|(dx, dy)| self.offset_wrapping(dx, dy, height, width))
+            .collect()
+    }
+
+    /// Moves `dx` columns right and `dy` rows down, or left and up if negative, wrapping around
+    /// the edges of a grid `height` rows tall and `width` columns wide, as when a creature leaving
+    /// one edge comes back on the opposite one. Panics if the grid is empty.
+    pub fn offset_wrapping(self, dx: isize, dy: isize, height: usize, width: usize) -> Self {
+        let wrap = |coordinate: usize, delta: isize, length: usize| {
+            let step = delta.unsigned_abs() % length;
+
+            if delta < 0 {
+                (coordinate % length + length - step) % length
+            } else {
+                (coordinate % length + step) % length
+            }
+        };
+
+        Self {
+            x: wrap(self.x, dx, width),
+            y: wrap(self.y, dy, height),
+        }
+    }
+
     fn bottom_left(self, height: usize) -> Option<Self> {
         if self.x > 0 && self.y < height - 1 {
             Some(Self {
@@ -116,3 +171,230 @@ impl Position {
         }
     }
 }
+
+/// A point on a grid without bounds, which may lie either side of the origin in each direction.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Coordinate {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Coordinate {
+    /// Returns the eight coordinates next to this one, including diagonally.
+    pub fn neighbours_all(self) -> [Self; 8] {
+        let Self { x, y } = self;
+
+        [
+            Self { x: x - 1, y: y + 1 },
+            Self { x: x + 1, y: y + 1 },
+            Self { x, y: y + 1 },
+            Self { x: x - 1, y },
+            Self { x: x + 1, y },
+            Self { x: x - 1, y: y - 1 },
+            Self { x: x + 1, y: y - 1 },
+            Self { x, y: y - 1 },
+        ]
+    }
+
+    /// Returns the four coordinates directly above, below, left and right of this one.
+    pub fn neighbours_orthogonal(self) -> [Self; 4] {
+        let Self { x, y } = self;
+
+        [
+            Self { x, y: y + 1 },
+            Self { x: x - 1, y },
+            Self { x: x + 1, y },
+            Self { x, y: y - 1 },
+        ]
+    }
+
+    /// Returns the sign of each part of the coordinate, which is the single step to take towards
+    /// it from the origin along a horizontal, vertical or diagonal line.
+    pub fn signum(self) -> Self {
+        Self {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+}
+
+impl Add for Coordinate {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl Sub for Coordinate {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl TryFrom<Position> for Coordinate {
+    type Error = TryFromIntError;
+
+    fn try_from(position: Position) -> Result<Self, Self::Error> {
+        Ok(Self {
+            x: isize::try_from(position.x)?,
+            y: isize::try_from(position.y)?,
+        })
+    }
+}
+
+/// Fails for coordinates left of or above the origin.
+impl TryFrom<Coordinate> for Position {
+    type Error = TryFromIntError;
+
+    fn try_from(coordinate: Coordinate) -> Result<Self, Self::Error> {
+        Ok(Self {
+            x: usize::try_from(coordinate.x)?,
+            y: usize::try_from(coordinate.y)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Coordinate, Position};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_neighbours_all_wrapping_at_a_corner() {
+        let expected = vec![
+            Position { x: 4, y: 1 },
+            Position { x: 1, y: 1 },
+            Position { x: 0, y: 1 },
+            Position { x: 4, y: 0 },
+            Position { x: 1, y: 0 },
+            Position { x: 4, y: 2 },
+            Position { x: 1, y: 2 },
+            Position { x: 0, y: 2 },
+        ];
+        let actual = Position { x: 0, y: 0 }.neighbours_all_wrapping(3, 5);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_all_wrapping_on_an_edge() {
+        let expected = vec![
+            Position { x: 1, y: 0 },
+            Position { x: 3, y: 0 },
+            Position { x: 2, y: 0 },
+            Position { x: 1, y: 2 },
+            Position { x: 3, y: 2 },
+            Position { x: 1, y: 1 },
+            Position { x: 3, y: 1 },
+            Position { x: 2, y: 1 },
+        ];
+        let actual = Position { x: 2, y: 2 }.neighbours_all_wrapping(3, 5);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_orthogonal_wrapping_at_a_corner() {
+        let expected = vec![
+            Position { x: 0, y: 1 },
+            Position { x: 4, y: 0 },
+            Position { x: 1, y: 0 },
+            Position { x: 0, y: 2 },
+        ];
+        let actual = Position { x: 0, y: 0 }.neighbours_orthogonal_wrapping(3, 5);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_orthogonal_wrapping_on_an_edge() {
+        let expected = vec![
+            Position { x: 2, y: 0 },
+            Position { x: 1, y: 2 },
+            Position { x: 3, y: 2 },
+            Position { x: 2, y: 1 },
+        ];
+        let actual = Position { x: 2, y: 2 }.neighbours_orthogonal_wrapping(3, 5);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_offset_wrapping_past_the_far_corner() {
+        let expected = Position { x: 0, y: 0 };
+        let actual = Position { x: 4, y: 2 }.offset_wrapping(1, 4, 3, 5);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_offset_wrapping_past_the_origin() {
+        let expected = Position { x: 4, y: 2 };
+        let actual = Position { x: 0, y: 0 }.offset_wrapping(-6, -4, 3, 5);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_all_past_the_origin() {
+        let expected = [
+            Coordinate { x: -1, y: 1 },
+            Coordinate { x: 1, y: 1 },
+            Coordinate { x: 0, y: 1 },
+            Coordinate { x: -1, y: 0 },
+            Coordinate { x: 1, y: 0 },
+            Coordinate { x: -1, y: -1 },
+            Coordinate { x: 1, y: -1 },
+            Coordinate { x: 0, y: -1 },
+        ];
+        let actual = Coordinate::default().neighbours_all();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_neighbours_orthogonal_past_the_origin() {
+        let expected = [
+            Coordinate { x: 0, y: 1 },
+            Coordinate { x: -1, y: 0 },
+            Coordinate { x: 1, y: 0 },
+            Coordinate { x: 0, y: -1 },
+        ];
+        let actual = Coordinate::default().neighbours_orthogonal();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_convert_from_position() {
+        let expected = Ok(Coordinate { x: 3, y: 4 });
+        let actual = Coordinate::try_from(Position { x: 3, y: 4 });
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_convert_to_position() {
+        let expected = Ok(Position { x: 3, y: 4 });
+        let actual = Position::try_from(Coordinate { x: 3, y: 4 });
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_convert_negative_coordinate_to_position() {
+        let actual = Position::try_from(Coordinate { x: 3, y: -1 });
+
+        assert!(actual.is_err());
+    }
+}
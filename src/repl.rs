@@ -0,0 +1,253 @@
+use crate::{input::Source, Day, Model, Part};
+use std::{path::Path, time::Instant};
+
+/// The commands a session understands, as shown by `help`.
+pub const HELP: &str = "commands:
+  load <day> [<profile> | <path>]  parse a day's input and keep it in memory
+  reload                           read and parse the loaded day's input again
+  run [1 | 2]                      solve one or both parts of the loaded day
+  with <value>                     solve the loaded day with its parameter set to <value>
+  time <on | off>                  show or hide how long each command takes
+  help                             show this message
+  quit                             end the session";
+
+const COMMANDS: &[&str] = &[
+    "load", "reload", "run", "with", "time", "help", "quit", "exit",
+];
+
+/// A day whose input has been parsed in a session.
+struct Loaded {
+    day: &'static Day,
+    source: Source,
+    model: Model,
+}
+
+/// An interactive session in which a day's input is parsed once, then solved as many times as
+/// needed, with its parameter changed as often as needed.
+pub struct Session {
+    loaded: Option<Loaded>,
+    timings: bool,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            loaded: None,
+            timings: true,
+        }
+    }
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Carries out a single `line` of input, returning what to print in response, or `None` once
+    /// the session has ended.
+    pub fn execute(&mut self, line: &str) -> Option<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        let output = match words[..] {
+            [] => Ok(String::new()),
+            ["quit" | "exit"] => return None,
+            ["help"] => Ok(String::from(HELP)),
+            ["load", day] => self.load(day, None),
+            ["load", day, source] => self.load(day, Some(source)),
+            ["reload"] => self.reload(),
+            ["run"] => self.run(&[Part::One, Part::Two]),
+            ["run", part] => part
+                .parse()
+                .map_err(|_| format!("invalid part: {}", part))
+                .and_then(|part| self.run(&[part])),
+            ["with", value] => value
+                .parse()
+                .map_err(|_| format!("invalid value: {}", value))
+                .and_then(|value| self.with(value)),
+            ["time", "on"] => self.set_timings(true),
+            ["time", "off"] => self.set_timings(false),
+            [command, ..] if COMMANDS.contains(&command) => {
+                Err(format!("invalid arguments to `{}`, try `help`", command))
+            }
+            [command, ..] => Err(format!("unknown command `{}`, try `help`", command)),
+        };
+
+        Some(output.unwrap_or_else(|message| format!("error: {}", message)))
+    }
+
+    fn load(&mut self, day: &str, source: Option<&str>) -> Result<String, String> {
+        let number: usize = day.parse().map_err(|_| format!("invalid day: {}", day))?;
+        let day = crate::day(number).ok_or_else(|| format!("no solution for day {}", number))?;
+        let source = match source {
+            // An existing path is read directly, while anything else names an input profile.
+            Some(path) if Path::new(path).exists() => Source::from(path),
+            Some(profile) => Source::profile(profile),
+            None => Source::default(),
+        };
+
+        self.parse(day, source)
+    }
+
+    fn reload(&mut self) -> Result<String, String> {
+        let loaded = self.loaded()?;
+
+        self.parse(loaded.day, loaded.source.clone())
+    }
+
+    fn parse(&mut self, day: &'static Day, source: Source) -> Result<String, String> {
+        let input = source.load(day).map_err(|e| e.to_string())?;
+        let start = Instant::now();
+        let model = day.parse(&input).map_err(|e| e.to_string())?;
+        let elapsed = start.elapsed();
+
+        let mut output = format!("loaded {}", day.name);
+        if let Some(path) = source.path(day) {
+            output += &format!(" from {}", path.display());
+        }
+        if self.timings {
+            output += &format!(" in {:.2?}", elapsed);
+        }
+        if let Some(parameter) = day.parameter {
+            output += &format!("\nparameter: {}", parameter);
+        }
+
+        self.loaded = Some(Loaded { day, source, model });
+        Ok(output)
+    }
+
+    fn run(&self, parts: &[Part]) -> Result<String, String> {
+        let loaded = self.loaded()?;
+        let lines: Vec<String> = parts
+            .iter()
+            .map(|part| {
+                let name = match part {
+                    Part::One => "part one",
+                    Part::Two => "part two",
+                };
+
+                self.time(name, || loaded.day.solve_model(&loaded.model, *part))
+            })
+            .collect();
+
+        Ok(lines.join("\n"))
+    }
+
+    fn with(&self, value: usize) -> Result<String, String> {
+        let loaded = self.loaded()?;
+        let parameter = loaded.day.parameter.unwrap_or("parameter");
+
+        Ok(self.time(&format!("{} = {}", parameter, value), || {
+            loaded.day.solve_with(&loaded.model, value)
+        }))
+    }
+
+    fn set_timings(&mut self, timings: bool) -> Result<String, String> {
+        self.timings = timings;
+
+        Ok(String::new())
+    }
+
+    fn loaded(&self) -> Result<&Loaded, String> {
+        self.loaded
+            .as_ref()
+            .ok_or_else(|| String::from("no day is loaded, try `load <day>`"))
+    }
+
+    /// Solves with `solve`, labelling the answer or error with `label` and how long it took.
+    fn time(&self, label: &str, solve: impl FnOnce() -> crate::Result<String>) -> String {
+        let start = Instant::now();
+        let answer = solve();
+        let elapsed = start.elapsed();
+
+        let timing = if self.timings {
+            format!(" ({:.2?})", elapsed)
+        } else {
+            String::new()
+        };
+
+        match answer {
+            // Drawings start on their own line so that their rows line up.
+            Ok(answer) if answer.contains('\n') => format!("{}:{}\n{}", label, timing, answer),
+            Ok(answer) => format!("{}: {}{}", label, answer, timing),
+            Err(error) => format!("{}: error: {}{}", label, error, timing),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+
+    /// Runs each line through a new session that leaves out timings, which vary between runs.
+    fn use_session(lines: &[&str]) -> Vec<Option<String>> {
+        let mut session = Session::new();
+        session.execute("time off");
+
+        lines.iter().map(|line| session.execute(line)).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "day_six")]
+    fn test_session_solves_loaded_day_with_parameter() {
+        let expected = vec![
+            Some(String::from(
+                "loaded day_six from input/example/day_six.txt\nparameter: duration",
+            )),
+            Some(String::from("part one: 5934\npart two: 26984457539")),
+            Some(String::from("duration = 18: 26")),
+            Some(String::from("part two: 26984457539")),
+        ];
+        let actual = use_session(&["load 6 example", "run", "with 18", "run 2"]);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_thirteen")]
+    fn test_session_with_more_folds_than_the_input_has() {
+        let expected = Some(String::from("folds = 18446744073709551615: 16"));
+        let actual = use_session(&["load 13 example", "with 18446744073709551615"])
+            .pop()
+            .unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "day_two")]
+    fn test_session_with_day_without_parameter() {
+        let expected = Some(String::from(
+            "parameter = 3: error: day 2: there is no parameter to change",
+        ));
+        let actual = use_session(&["load 2 example", "with 3"]).pop().unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_session_without_loaded_day() {
+        let expected = vec![
+            Some(String::from("error: no day is loaded, try `load <day>`")),
+            Some(String::from("error: no day is loaded, try `load <day>`")),
+        ];
+        let actual = use_session(&["run", "with 18"]);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_session_with_invalid_commands() {
+        let expected = vec![
+            Some(String::from("error: unknown command `solve`, try `help`")),
+            Some(String::from(
+                "error: invalid arguments to `load`, try `help`",
+            )),
+            Some(String::from("error: invalid day: six")),
+            Some(String::new()),
+            None,
+        ];
+        let actual = use_session(&["solve 6", "load", "load six", "  ", "quit"]);
+
+        assert_eq!(expected, actual);
+    }
+}
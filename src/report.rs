@@ -0,0 +1,481 @@
+use crate::{input::Source, time, Answer, Error, Model, Part, Result, Run, DAYS};
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    str::FromStr,
+    sync::{Condvar, Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The outcome of running both parts of a single registered day against its input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    pub day: usize,
+    pub name: &'static str,
+    /// The answers and timings, or why the input could not be loaded or parsed.
+    pub run: Result<Run>,
+}
+
+/// How a report is written out.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// Aligned columns for reading in a terminal.
+    Table,
+    Json,
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Runs every registered day against its input from `source`, spread across up to `threads`
+/// worker threads. Each day's input is loaded and parsed once, after which its two parts are queued
+/// as separate jobs sharing the parsed model, so they may be solved on different workers at the
+/// same time. Entries are returned in day order however the jobs were scheduled.
+pub fn run_all(source: &Source, threads: usize) -> Vec<Entry> {
+    let parsed: Vec<OnceLock<Result<(Model, Duration)>>> =
+        DAYS.iter().map(|_| OnceLock::new()).collect();
+    let answers: Vec<[Mutex<Option<Result<Answer>>>; 2]> = DAYS
+        .iter()
+        .map(|_| [Mutex::new(None), Mutex::new(None)])
+        .collect();
+    let queue = Queue::new((0..DAYS.len()).map(Job::Parse));
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, (2 * DAYS.len()).max(1)) {
+            scope.spawn(|| {
+                while let Some(job) = queue.next() {
+                    match job {
+                        Job::Parse(ix) => {
+                            let day = &DAYS[ix];
+                            let model = parsed[ix].get_or_init(|| {
+                                let input = source.load(day)?;
+                                let start = Instant::now();
+                                let model = day.parse(&input)?;
+
+                                Ok((model, start.elapsed()))
+                            });
+
+                            queue.parsed(match model {
+                                Ok(_) => vec![Job::Solve(ix, Part::One), Job::Solve(ix, Part::Two)],
+                                Err(_) => vec![],
+                            });
+                        }
+                        Job::Solve(ix, part) => {
+                            let (model, _) = parsed[ix]
+                                .get()
+                                .and_then(|model| model.as_ref().ok())
+                                .expect("Day is parsed before it is solved");
+                            let answer = time(|| DAYS[ix].solve_model(model, part));
+
+                            *answers[ix][part_index(part)]
+                                .lock()
+                                .expect("Worker panicked") = Some(answer);
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    DAYS.iter()
+        .zip(parsed)
+        .zip(answers)
+        .map(|((day, model), [part_one, part_two])| {
+            let answer = |answer: Mutex<Option<Result<Answer>>>| {
+                answer
+                    .into_inner()
+                    .expect("Worker panicked")
+                    .expect("Both parts of a parsed day are solved")
+            };
+            let run = model
+                .into_inner()
+                .expect("Every day is parsed")
+                .map(|(_, parse_time)| Run {
+                    parse_time,
+                    part_one: answer(part_one),
+                    part_two: answer(part_two),
+                });
+
+            Entry {
+                day: day.number,
+                name: day.name,
+                run,
+            }
+        })
+        .collect()
+}
+
+fn part_index(part: Part) -> usize {
+    match part {
+        Part::One => 0,
+        Part::Two => 1,
+    }
+}
+
+/// A unit of work for [`run_all`]'s workers, given by the index of a day in [`DAYS`].
+#[derive(Clone, Copy, Debug)]
+enum Job {
+    Parse(usize),
+    Solve(usize, Part),
+}
+
+/// The jobs waiting for a worker, alongside how many days are still being parsed. Workers wait
+/// for the days being parsed rather than stopping, as each may yet queue the jobs to solve it.
+struct Queue {
+    state: Mutex<QueueState>,
+    changed: Condvar,
+}
+
+struct QueueState {
+    waiting: VecDeque<Job>,
+    parsing: usize,
+}
+
+impl Queue {
+    fn new(jobs: impl IntoIterator<Item = Job>) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                waiting: jobs.into_iter().collect(),
+                parsing: 0,
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Takes the next job, waiting while there is none but more may still be queued. Returns
+    /// `None` once every job has been taken and no more can be queued.
+    fn next(&self) -> Option<Job> {
+        let mut state = self.state.lock().expect("Worker panicked");
+
+        loop {
+            if let Some(job) = state.waiting.pop_front() {
+                if let Job::Parse(_) = job {
+                    state.parsing += 1;
+                }
+
+                return Some(job);
+            }
+
+            if state.parsing == 0 {
+                return None;
+            }
+
+            state = self.changed.wait(state).expect("Worker panicked");
+        }
+    }
+
+    /// Marks a day as parsed, queueing the `jobs` that follow on from it.
+    fn parsed(&self, jobs: impl IntoIterator<Item = Job>) {
+        let mut state = self.state.lock().expect("Worker panicked");
+        state.waiting.extend(jobs);
+        state.parsing -= 1;
+        self.changed.notify_all();
+    }
+}
+
+/// Writes the answers and timings in `entries` out in the given `format`.
+pub fn render(entries: &[Entry], format: Format) -> String {
+    match format {
+        Format::Table => render_table(entries),
+        Format::Json => render_json(entries),
+        Format::Markdown => render_markdown(entries),
+    }
+}
+
+/// A single row of a table, with each column already formatted.
+struct Row {
+    day: String,
+    part: String,
+    answer: String,
+    parse: String,
+    solve: String,
+}
+
+fn rows(entries: &[Entry]) -> Vec<Row> {
+    entries
+        .iter()
+        .flat_map(|entry| {
+            let day = entry.day.to_string();
+
+            match &entry.run {
+                Ok(run) => [(Part::One, "1"), (Part::Two, "2")]
+                    .iter()
+                    .map(|(part, number)| {
+                        let (answer, solve) = match run.part(*part) {
+                            Ok(answer) => (
+                                answer.value.lines().collect::<Vec<_>>().join(" / "),
+                                format_duration(answer.solve_time),
+                            ),
+                            Err(error) => (format!("error: {}", error), String::from("-")),
+                        };
+
+                        Row {
+                            day: day.clone(),
+                            part: number.to_string(),
+                            answer,
+                            parse: format_duration(run.parse_time),
+                            solve,
+                        }
+                    })
+                    .collect(),
+                Err(error) => vec![Row {
+                    day,
+                    part: String::from("-"),
+                    answer: format!("error: {}", error),
+                    parse: String::from("-"),
+                    solve: String::from("-"),
+                }],
+            }
+        })
+        .collect()
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+fn render_table(entries: &[Entry]) -> String {
+    let rows = rows(entries);
+    let width = rows
+        .iter()
+        .map(|row| row.answer.chars().count())
+        .chain([6])
+        .max()
+        .unwrap_or_default();
+
+    let mut output = format!(
+        "{:>3}  {:>4}  {:<width$}  {:>10}  {:>10}\n",
+        "Day",
+        "Part",
+        "Answer",
+        "Parse",
+        "Solve",
+        width = width
+    );
+
+    for row in rows {
+        writeln!(
+            output,
+            "{:>3}  {:>4}  {:<width$}  {:>10}  {:>10}",
+            row.day,
+            row.part,
+            row.answer,
+            row.parse,
+            row.solve,
+            width = width
+        )
+        .expect("Writing to a string cannot fail");
+    }
+
+    output
+}
+
+fn render_markdown(entries: &[Entry]) -> String {
+    let mut output = String::from(
+        "| Day | Part | Answer | Parse | Solve |\n|----:|-----:|:-------|------:|------:|\n",
+    );
+
+    for row in rows(entries) {
+        writeln!(
+            output,
+            "| {} | {} | {} | {} | {} |",
+            row.day,
+            row.part,
+            row.answer.replace('|', "\\|"),
+            row.parse,
+            row.solve
+        )
+        .expect("Writing to a string cannot fail");
+    }
+
+    output
+}
+
+fn render_json(entries: &[Entry]) -> String {
+    let days: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let fields = match &entry.run {
+                Ok(run) => {
+                    let parts: Vec<String> = [(Part::One, 1), (Part::Two, 2)]
+                        .iter()
+                        .map(|(part, number)| match run.part(*part) {
+                            Ok(answer) => format!(
+                                "{{\"part\": {}, \"answer\": {}, \"solve_ns\": {}}}",
+                                number,
+                                json_string(&answer.value),
+                                answer.solve_time.as_nanos()
+                            ),
+                            Err(error) => format!(
+                                "{{\"part\": {}, \"error\": {}}}",
+                                number,
+                                json_error(error)
+                            ),
+                        })
+                        .collect();
+
+                    format!(
+                        "\"parse_ns\": {}, \"parts\": [{}]",
+                        run.parse_time.as_nanos(),
+                        parts.join(", ")
+                    )
+                }
+                Err(error) => format!("\"error\": {}", json_error(error)),
+            };
+
+            format!(
+                "  {{\"day\": {}, \"name\": {}, {}}}",
+                entry.day,
+                json_string(entry.name),
+                fields
+            )
+        })
+        .collect();
+
+    format!("[\n{}\n]\n", days.join(",\n"))
+}
+
+fn json_error(error: &Error) -> String {
+    json_string(&error.to_string())
+}
+
+/// Quotes `s` as a JSON string, escaping anything that may not appear in one literally.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                write!(quoted, "\\u{:04x}", c as u32).expect("Writing to a string cannot fail")
+            }
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, Format};
+    use crate::{input::Source, Answer, Error, Result, Run, DAYS};
+    use std::time::Duration;
+
+    fn use_example_entries() -> Vec<Entry> {
+        vec![
+            Entry {
+                day: 1,
+                name: "day_one",
+                run: Ok(Run {
+                    parse_time: Duration::from_micros(12),
+                    part_one: Ok(Answer {
+                        value: String::from("7"),
+                        solve_time: Duration::from_nanos(1500),
+                    }),
+                    part_two: Err(Error::unsolvable(1, "no \"window\"")),
+                }),
+            },
+            Entry {
+                day: 2,
+                name: "day_two",
+                run: Err(Error::parse(2, "up x", "invalid units").at_line(3)),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_table() {
+        let expected = r#"Day  Part  Answer                                            Parse       Solve
+  1     1  7                                               12.00µs      1.50µs
+  1     2  error: day 1: no "window"                       12.00µs           -
+  2     -  error: day 2, line 3: invalid units: `up x`           -           -
+"#;
+        let actual = super::render(&use_example_entries(), Format::Table);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let expected = r#"| Day | Part | Answer | Parse | Solve |
+|----:|-----:|:-------|------:|------:|
+| 1 | 1 | 7 | 12.00µs | 1.50µs |
+| 1 | 2 | error: day 1: no "window" | 12.00µs | - |
+| 2 | - | error: day 2, line 3: invalid units: `up x` | - | - |
+"#;
+        let actual = super::render(&use_example_entries(), Format::Markdown);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_render_json() {
+        let expected = r#"[
+  {"day": 1, "name": "day_one", "parse_ns": 12000, "parts": [{"part": 1, "answer": "7", "solve_ns": 1500}, {"part": 2, "error": "day 1: no \"window\""}]},
+  {"day": 2, "name": "day_two", "error": "day 2, line 3: invalid units: `up x`"}
+]
+"#;
+        let actual = super::render(&use_example_entries(), Format::Json);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_run_all_in_parallel_keeps_day_order() {
+        let source = Source::profile("example");
+        let expected: Vec<(usize, &str)> = DAYS.iter().map(|day| (day.number, day.name)).collect();
+        let actual: Vec<(usize, &str)> = super::run_all(&source, 4)
+            .into_iter()
+            .map(|entry| (entry.day, entry.name))
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_run_all_in_parallel_matches_one_thread() {
+        let source = Source::profile("example");
+        let answers = |entries: Vec<Entry>| -> Vec<(usize, Result<[Result<String>; 2]>)> {
+            entries
+                .into_iter()
+                .map(|entry| {
+                    let values = entry.run.map(|run| {
+                        [run.part_one, run.part_two].map(|answer| answer.map(|answer| answer.value))
+                    });
+
+                    (entry.day, values)
+                })
+                .collect()
+        };
+        let expected = answers(super::run_all(&source, 1));
+        let actual = answers(super::run_all(&source, 4));
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_json_string_escapes_control_characters() {
+        let expected = "\"#.\\n.#\\u0007\"";
+        let actual = super::json_string("#.\n.#\u{7}");
+
+        assert_eq!(expected, actual);
+    }
+}
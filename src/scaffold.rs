@@ -0,0 +1,454 @@
+use crate::input;
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// The module a new day is generated from, with `{{name}}`, `{{type}}` and `{{number}}` standing
+/// in for the day's module name, solution type and number.
+const TEMPLATE: &str = include_str!("scaffold/day.rs.tmpl");
+
+/// How each day of the calendar is spelt in module names, from day one.
+const NUMBERS: [&str; 25] = [
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+    "twenty_one",
+    "twenty_two",
+    "twenty_three",
+    "twenty_four",
+    "twenty_five",
+];
+
+/// Returns the module name for the given `day`, such as `day_sixteen`, or `None` if it is not a
+/// day of the calendar.
+pub fn module_name(day: usize) -> Option<String> {
+    day.checked_sub(1)
+        .and_then(|ix| NUMBERS.get(ix))
+        .map(|number| format!("day_{}", number))
+}
+
+/// Returns the name of the solution type for the given `day`, such as `DaySixteen`.
+pub fn type_name(day: usize) -> Option<String> {
+    module_name(day).map(|name| {
+        name.split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect()
+    })
+}
+
+/// Returns the day with the given module name, such as `day_sixteen`, whether or not it is solved.
+pub(crate) fn day_of(module_name: &str) -> Option<usize> {
+    NUMBERS
+        .iter()
+        .position(|number| module_name.strip_prefix("day_") == Some(number))
+        .map(|ix| ix + 1)
+}
+
+/// Returns the source of a new module for the given `day`, with an unsolved [`crate::Solution`]
+/// and ignored tests against its example and real inputs.
+pub fn render_module(day: usize) -> Option<String> {
+    let name = module_name(day)?;
+    let type_name = type_name(day)?;
+
+    Some(
+        TEMPLATE
+            .replace("{{name}}", &name)
+            .replace("{{type}}", &type_name)
+            .replace("{{number}}", &day.to_string()),
+    )
+}
+
+/// Adds the module declaration and registry entry for the given `day` to the source of `lib.rs`,
+/// each in order and gated behind the day's feature. Returns `None` if the day is already declared,
+/// or either place to add it to cannot be found.
+pub fn register(lib: &str, day: usize) -> Option<String> {
+    let name = module_name(day)?;
+    let type_name = type_name(day)?;
+    let mut lines: Vec<String> = lib.lines().map(String::from).collect();
+
+    let modules: Vec<(usize, &str)> = lib
+        .lines()
+        .enumerate()
+        .filter_map(|(ix, line)| {
+            line.strip_prefix("pub mod ")
+                .or_else(|| line.strip_prefix("mod "))
+                .and_then(|module| module.strip_suffix(';'))
+                .map(|module| (ix, module))
+        })
+        .collect();
+
+    if modules.iter().any(|(_, module)| *module == name) {
+        return None;
+    }
+
+    let start = lib
+        .lines()
+        .position(|line| line.starts_with("pub static DAYS"))?;
+    let end = start + lib.lines().skip(start).position(|line| line == "];")?;
+    let entry_ix = (start + 1..end)
+        .find(|ix| {
+            lines[*ix]
+                .split('"')
+                .nth(1)
+                .and_then(day_of)
+                .is_some_and(|registered| registered > day)
+        })
+        .unwrap_or(end);
+    lines.splice(
+        entry_ix..entry_ix,
+        [
+            format!("    {}", feature_gate(&name)),
+            format!("    Day::new::<{}::{}>(\"{}\"),", name, type_name, name),
+        ],
+    );
+
+    let module_ix = match modules.iter().find(|(_, module)| *module > name.as_str()) {
+        // Keep the following module's attributes with it.
+        Some((ix, _)) => (0..*ix)
+            .rev()
+            .take_while(|ix| lines[*ix].starts_with("#["))
+            .last()
+            .unwrap_or(*ix),
+        None => modules.last()?.0 + 1,
+    };
+    lines.splice(
+        module_ix..module_ix,
+        [feature_gate(&name), format!("pub mod {};", name)],
+    );
+
+    Some(lines.join("\n") + "\n")
+}
+
+fn feature_gate(name: &str) -> String {
+    format!("#[cfg(feature = \"{}\")]", name)
+}
+
+/// Adds a feature for the given `day` to the source of `Cargo.toml`, and enables it as part of the
+/// `all` feature, each in order. Returns `None` if the feature already exists, or the `all`
+/// feature cannot be found.
+pub fn register_feature(manifest: &str, day: usize) -> Option<String> {
+    let name = module_name(day)?;
+    let mut lines: Vec<String> = manifest.lines().map(String::from).collect();
+    let feature_of = |line: &str| {
+        line.split_once('=')
+            .map(|(feature, _)| feature.trim())
+            .and_then(day_of)
+    };
+
+    if lines.iter().any(|line| feature_of(line) == Some(day)) {
+        return None;
+    }
+
+    let start = lines.iter().position(|line| line == "all = [")?;
+    let end = start + lines.iter().skip(start).position(|line| line == "]")?;
+    let features: Vec<(usize, usize)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, line)| feature_of(line).map(|day| (ix, day)))
+        .collect();
+
+    let feature_ix = match features.iter().find(|(_, registered)| *registered > day) {
+        Some((ix, _)) => *ix,
+        None => features.last().map_or(end, |(ix, _)| *ix) + 1,
+    };
+    lines.insert(feature_ix, format!("{} = []", name));
+
+    let enabled_ix = (start + 1..end)
+        .find(|ix| {
+            day_of(lines[*ix].trim().trim_matches(|c| c == '"' || c == ','))
+                .is_some_and(|enabled| enabled > day)
+        })
+        .unwrap_or(end);
+    lines.insert(enabled_ix, format!("    \"{}\",", name));
+
+    Some(lines.join("\n") + "\n")
+}
+
+/// Generates the module for the given `day` in the crate at `root`, registers it in `lib.rs`, adds
+/// its feature to `Cargo.toml` and creates an empty input file for it in every profile. Returns
+/// the paths written to.
+pub fn generate(root: &Path, day: usize) -> io::Result<Vec<PathBuf>> {
+    let name = module_name(day)
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "day must be between 1 and 25"))?;
+    let module_path = root.join("src").join(format!("{}.rs", name));
+
+    if module_path.exists() {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", module_path.display()),
+        ));
+    }
+
+    let lib_path = root.join("src").join("lib.rs");
+    let lib = register(&fs::read_to_string(&lib_path)?, day).ok_or_else(|| {
+        io::Error::new(
+            ErrorKind::InvalidData,
+            format!("could not register {} in {}", name, lib_path.display()),
+        )
+    })?;
+    let manifest_path = root.join("Cargo.toml");
+    let manifest =
+        register_feature(&fs::read_to_string(&manifest_path)?, day).ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "could not add a {} feature to {}",
+                    name,
+                    manifest_path.display()
+                ),
+            )
+        })?;
+    let module = render_module(day).expect("Day has a module name");
+
+    let input_directory = root.join(input::DEFAULT_DIRECTORY);
+    let mut profiles = input::profiles(&input_directory).unwrap_or_default();
+    for profile in [input::DEFAULT_PROFILE, "example"] {
+        if !profiles.iter().any(|p| p == profile) {
+            profiles.push(profile.to_string());
+        }
+    }
+
+    fs::write(&module_path, module)?;
+    fs::write(&lib_path, lib)?;
+    fs::write(&manifest_path, manifest)?;
+    let mut written = vec![module_path, lib_path, manifest_path];
+
+    for profile in profiles {
+        let directory = input_directory.join(profile);
+        let path = directory.join(format!("{}.txt", name));
+
+        if !path.exists() {
+            fs::create_dir_all(&directory)?;
+            fs::write(&path, "")?;
+            written.push(path);
+        }
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DAYS;
+
+    const LIB: &str = r#"pub mod answers;
+#[cfg(feature = "day_fifteen")]
+pub mod day_fifteen;
+#[cfg(feature = "day_six")]
+pub mod day_six;
+#[cfg(feature = "day_ten")]
+pub mod day_ten;
+mod error;
+
+pub static DAYS: &[Day] = &[
+    #[cfg(feature = "day_six")]
+    Day::new::<day_six::DaySix>("day_six"),
+    #[cfg(feature = "day_ten")]
+    Day::new::<day_ten::DayTen>("day_ten"),
+    #[cfg(feature = "day_fifteen")]
+    Day::new::<day_fifteen::DayFifteen>("day_fifteen"),
+];
+"#;
+
+    const MANIFEST: &str = r#"[features]
+default = ["all"]
+all = [
+    "day_one",
+    "day_fifteen",
+]
+day_one = []
+day_fifteen = []
+
+[dependencies]
+"#;
+
+    #[test]
+    fn test_names() {
+        let expected = vec![
+            (
+                Some(String::from("day_sixteen")),
+                Some(String::from("DaySixteen")),
+            ),
+            (
+                Some(String::from("day_twenty_one")),
+                Some(String::from("DayTwentyOne")),
+            ),
+            (None, None),
+        ];
+        let actual: Vec<_> = [16, 21, 26]
+            .iter()
+            .map(|day| (super::module_name(*day), super::type_name(*day)))
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_registered_days_follow_naming() {
+        let expected: Vec<Option<String>> = DAYS.iter().map(|day| Some(day.name.into())).collect();
+        let actual: Vec<Option<String>> = DAYS
+            .iter()
+            .map(|day| super::module_name(day.number))
+            .collect();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_register_new_day() {
+        let expected = String::from(
+            r#"pub mod answers;
+#[cfg(feature = "day_fifteen")]
+pub mod day_fifteen;
+#[cfg(feature = "day_six")]
+pub mod day_six;
+#[cfg(feature = "day_sixteen")]
+pub mod day_sixteen;
+#[cfg(feature = "day_ten")]
+pub mod day_ten;
+mod error;
+
+pub static DAYS: &[Day] = &[
+    #[cfg(feature = "day_six")]
+    Day::new::<day_six::DaySix>("day_six"),
+    #[cfg(feature = "day_ten")]
+    Day::new::<day_ten::DayTen>("day_ten"),
+    #[cfg(feature = "day_fifteen")]
+    Day::new::<day_fifteen::DayFifteen>("day_fifteen"),
+    #[cfg(feature = "day_sixteen")]
+    Day::new::<day_sixteen::DaySixteen>("day_sixteen"),
+];
+"#,
+        );
+        let actual = super::register(LIB, 16);
+
+        assert_eq!(Some(expected), actual);
+    }
+
+    #[test]
+    fn test_register_day_between_days() {
+        let expected = String::from(
+            r#"pub mod answers;
+#[cfg(feature = "day_fifteen")]
+pub mod day_fifteen;
+#[cfg(feature = "day_seven")]
+pub mod day_seven;
+#[cfg(feature = "day_six")]
+pub mod day_six;
+#[cfg(feature = "day_ten")]
+pub mod day_ten;
+mod error;
+
+pub static DAYS: &[Day] = &[
+    #[cfg(feature = "day_six")]
+    Day::new::<day_six::DaySix>("day_six"),
+    #[cfg(feature = "day_seven")]
+    Day::new::<day_seven::DaySeven>("day_seven"),
+    #[cfg(feature = "day_ten")]
+    Day::new::<day_ten::DayTen>("day_ten"),
+    #[cfg(feature = "day_fifteen")]
+    Day::new::<day_fifteen::DayFifteen>("day_fifteen"),
+];
+"#,
+        );
+        let actual = super::register(LIB, 7);
+
+        assert_eq!(Some(expected), actual);
+    }
+
+    #[test]
+    fn test_register_existing_day() {
+        let actual = super::register(LIB, 6);
+
+        assert!(actual.is_none());
+    }
+
+    #[test]
+    fn test_register_feature() {
+        let expected = String::from(
+            r#"[features]
+default = ["all"]
+all = [
+    "day_one",
+    "day_fifteen",
+    "day_sixteen",
+]
+day_one = []
+day_fifteen = []
+day_sixteen = []
+
+[dependencies]
+"#,
+        );
+        let actual = super::register_feature(MANIFEST, 16);
+
+        assert_eq!(Some(expected), actual);
+    }
+
+    #[test]
+    fn test_register_feature_between_features() {
+        let expected = String::from(
+            r#"[features]
+default = ["all"]
+all = [
+    "day_one",
+    "day_two",
+    "day_fifteen",
+]
+day_one = []
+day_two = []
+day_fifteen = []
+
+[dependencies]
+"#,
+        );
+        let actual = super::register_feature(MANIFEST, 2);
+
+        assert_eq!(Some(expected), actual);
+    }
+
+    #[test]
+    fn test_register_existing_feature() {
+        let actual = super::register_feature(MANIFEST, 15);
+
+        assert!(actual.is_none());
+    }
+
+    #[test]
+    fn test_render_module() {
+        let module = super::render_module(16).unwrap();
+        let expected = (true, true, true);
+        let actual = (
+            module.contains("pub struct DaySixteen;"),
+            module.contains("const DAY: usize = 16;"),
+            module.contains("include_str!(\"../input/default/day_sixteen.txt\")"),
+        );
+
+        assert_eq!(expected, actual);
+    }
+}